@@ -0,0 +1,60 @@
+#[macro_use]
+extern crate criterion;
+extern crate tutcommon;
+
+use std::hint::black_box;
+
+use criterion::Criterion;
+
+use tutcommon::matrix::{Matrix4f, Vector3f, Vector4f};
+
+fn view_projection() -> Matrix4f {
+    Matrix4f::perspective(45.0, 4.0 / 3.0, 0.1, 100.0).mul(&Matrix4f::look_at(
+        &Vector3f(4.0, 3.0, 3.0),
+        &Vector3f(0.0, 0.0, 0.0),
+        &Vector3f(0.0, 1.0, 0.0),
+    ))
+}
+
+fn matrix_mul(c: &mut Criterion) {
+    let a = view_projection();
+    let b = Matrix4f::rotate(30.0, &Vector3f(1.0, 2.0, 3.0));
+
+    c.bench_function("matrix * matrix", |bench| {
+        bench.iter(|| black_box(&a).mul(black_box(&b)))
+    });
+
+    let v = Vector4f(1.0, 2.0, 3.0, 1.0);
+    c.bench_function("matrix * vector", |bench| {
+        bench.iter(|| black_box(&a) * black_box(v))
+    });
+}
+
+fn instances_mvp(c: &mut Criterion) {
+    let projection = Matrix4f::perspective(45.0, 4.0 / 3.0, 0.1, 100.0);
+    let view = Matrix4f::look_at(
+        &Vector3f(4.0, 3.0, 3.0),
+        &Vector3f(0.0, 0.0, 0.0),
+        &Vector3f(0.0, 1.0, 0.0),
+    );
+    let models: Vec<Matrix4f> = (0..10_000)
+        .map(|i| {
+            let f = i as f32;
+            Matrix4f::translate(&Vector3f(f.sin() * 10.0, f.cos() * 10.0, f * 0.01))
+                .mul(&Matrix4f::rotate(f, &Vector3f(0.0, 1.0, 0.0)))
+        })
+        .collect();
+
+    // The same chain as in tutorial `draw` functions.
+    c.bench_function("mvp for 10000 instances", |bench| {
+        bench.iter(|| {
+            models
+                .iter()
+                .map(|model| projection.mul(&view).mul(model))
+                .fold(0.0, |acc, mvp| acc + mvp.columns()[3][3])
+        })
+    });
+}
+
+criterion_group!(benches, matrix_mul, instances_mvp);
+criterion_main!(benches);
@@ -0,0 +1,416 @@
+#![doc = "Colors in linear and sRGB spaces."]
+//! Lighting should be computed with linear colors, sRGB colors are what
+//! color pickers, image files and hex codes use.
+
+use std::{error::Error, fmt, str::FromStr};
+
+#[doc = "Convert sRGB encoded component to linear one."]
+/// Uses exact piecewise sRGB transfer function, not gamma 2.2.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[doc = "Convert linear component to sRGB encoded one."]
+/// Uses exact piecewise sRGB transfer function, not gamma 2.2.
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// Component in 0..1 from 8-bit value.
+fn from_u8(c: u8) -> f32 {
+    f32::from(c) / 255.0
+}
+
+// 8-bit value of component, it's clamped to 0..1.
+fn to_u8(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[doc = "Linear RGB color."]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rgb {
+    #[doc = "Red."]
+    pub r: f32,
+
+    #[doc = "Green."]
+    pub g: f32,
+
+    #[doc = "Blue."]
+    pub b: f32,
+}
+
+impl Rgb {
+    #[doc = "Create color from components."]
+    pub fn new(r: f32, g: f32, b: f32) -> Rgb {
+        Rgb { r, g, b }
+    }
+
+    #[doc = "Encode color to sRGB."]
+    pub fn to_srgb(&self) -> Srgb {
+        Srgb::new(
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+        )
+    }
+
+    #[doc = "Add alpha."]
+    pub fn with_alpha(&self, a: f32) -> Rgba {
+        Rgba::new(self.r, self.g, self.b, a)
+    }
+
+    #[doc = "Relative luminance."]
+    pub fn luminance(&self) -> f32 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    #[doc = "Linear interpolation between colors."]
+    pub fn lerp(&self, other: &Rgb, t: f32) -> Rgb {
+        Rgb::new(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+        )
+    }
+}
+
+impl From<Srgb> for Rgb {
+    fn from(c: Srgb) -> Rgb {
+        c.to_linear()
+    }
+}
+
+#[doc = "Linear RGB color with alpha."]
+/// Alpha is linear in both spaces.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rgba {
+    #[doc = "Red."]
+    pub r: f32,
+
+    #[doc = "Green."]
+    pub g: f32,
+
+    #[doc = "Blue."]
+    pub b: f32,
+
+    #[doc = "Alpha, 1 is opaque."]
+    pub a: f32,
+}
+
+impl Rgba {
+    #[doc = "Create color from components."]
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Rgba {
+        Rgba { r, g, b, a }
+    }
+
+    #[doc = "Encode color to sRGB."]
+    pub fn to_srgb(&self) -> Srgba {
+        self.rgb().to_srgb().with_alpha(self.a)
+    }
+
+    #[doc = "Drop alpha."]
+    pub fn rgb(&self) -> Rgb {
+        Rgb::new(self.r, self.g, self.b)
+    }
+
+    #[doc = "Color with alpha multiplied into other components."]
+    pub fn premultiply(&self) -> Rgba {
+        Rgba::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+}
+
+impl From<Srgba> for Rgba {
+    fn from(c: Srgba) -> Rgba {
+        c.to_linear()
+    }
+}
+
+#[doc = "sRGB encoded color."]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Srgb {
+    #[doc = "Red."]
+    pub r: f32,
+
+    #[doc = "Green."]
+    pub g: f32,
+
+    #[doc = "Blue."]
+    pub b: f32,
+}
+
+impl Srgb {
+    #[doc = "Create color from components."]
+    pub fn new(r: f32, g: f32, b: f32) -> Srgb {
+        Srgb { r, g, b }
+    }
+
+    #[doc = "Create color from 8-bit components."]
+    pub fn from_u8(r: u8, g: u8, b: u8) -> Srgb {
+        Srgb::new(from_u8(r), from_u8(g), from_u8(b))
+    }
+
+    #[doc = "8-bit components, they are clamped and rounded."]
+    pub fn to_u8(&self) -> [u8; 3] {
+        [to_u8(self.r), to_u8(self.g), to_u8(self.b)]
+    }
+
+    #[doc = "Parse hex code like `#ff8000` or `f80`."]
+    pub fn from_hex(s: &str) -> Result<Srgb, ParseColorError> {
+        match parse_hex(s)? {
+            (c, None) => Ok(Srgb::from_u8(c[0], c[1], c[2])),
+            (_, Some(_)) => Err(ParseColorError),
+        }
+    }
+
+    #[doc = "Hex code like `#ff8000`."]
+    pub fn to_hex(&self) -> String {
+        let c = self.to_u8();
+
+        format!("#{:02x}{:02x}{:02x}", c[0], c[1], c[2])
+    }
+
+    #[doc = "Decode color to linear space."]
+    pub fn to_linear(&self) -> Rgb {
+        Rgb::new(
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+        )
+    }
+
+    #[doc = "Add alpha."]
+    pub fn with_alpha(&self, a: f32) -> Srgba {
+        Srgba::new(self.r, self.g, self.b, a)
+    }
+}
+
+impl From<Rgb> for Srgb {
+    fn from(c: Rgb) -> Srgb {
+        c.to_srgb()
+    }
+}
+
+impl FromStr for Srgb {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Srgb, ParseColorError> {
+        Srgb::from_hex(s)
+    }
+}
+
+#[doc = "sRGB encoded color with alpha."]
+/// Alpha is linear in both spaces.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Srgba {
+    #[doc = "Red."]
+    pub r: f32,
+
+    #[doc = "Green."]
+    pub g: f32,
+
+    #[doc = "Blue."]
+    pub b: f32,
+
+    #[doc = "Alpha, 1 is opaque."]
+    pub a: f32,
+}
+
+impl Srgba {
+    #[doc = "Create color from components."]
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Srgba {
+        Srgba { r, g, b, a }
+    }
+
+    #[doc = "Create color from 8-bit components."]
+    pub fn from_u8(r: u8, g: u8, b: u8, a: u8) -> Srgba {
+        Srgba::new(from_u8(r), from_u8(g), from_u8(b), from_u8(a))
+    }
+
+    #[doc = "8-bit components, they are clamped and rounded."]
+    pub fn to_u8(&self) -> [u8; 4] {
+        [to_u8(self.r), to_u8(self.g), to_u8(self.b), to_u8(self.a)]
+    }
+
+    #[doc = "Parse hex code like `#ff800080`, `#ff8000`, `f808` or `f80`."]
+    /// Alpha is 1 if it's omitted.
+    pub fn from_hex(s: &str) -> Result<Srgba, ParseColorError> {
+        let (c, a) = parse_hex(s)?;
+
+        Ok(Srgba::from_u8(c[0], c[1], c[2], a.unwrap_or(255)))
+    }
+
+    #[doc = "Hex code like `#ff800080`."]
+    pub fn to_hex(&self) -> String {
+        let c = self.to_u8();
+
+        format!("#{:02x}{:02x}{:02x}{:02x}", c[0], c[1], c[2], c[3])
+    }
+
+    #[doc = "Decode color to linear space."]
+    pub fn to_linear(&self) -> Rgba {
+        self.rgb().to_linear().with_alpha(self.a)
+    }
+
+    #[doc = "Drop alpha."]
+    pub fn rgb(&self) -> Srgb {
+        Srgb::new(self.r, self.g, self.b)
+    }
+}
+
+impl From<Rgba> for Srgba {
+    fn from(c: Rgba) -> Srgba {
+        c.to_srgb()
+    }
+}
+
+impl FromStr for Srgba {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Srgba, ParseColorError> {
+        Srgba::from_hex(s)
+    }
+}
+
+#[doc = "Error of parsing hex color code."]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseColorError;
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid hex color code")
+    }
+}
+
+impl Error for ParseColorError {}
+
+// RGB and optional alpha from hex code with 3, 4, 6 or 8 digits.
+fn parse_hex(s: &str) -> Result<([u8; 3], Option<u8>), ParseColorError> {
+    let s = s.strip_prefix('#').unwrap_or(s);
+    let digits = s
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(ParseColorError)?;
+
+    let bytes: Vec<u8> = match digits.len() {
+        // Short form repeats each digit.
+        3 | 4 => digits.iter().map(|d| d * 17).collect(),
+        6 | 8 => digits.chunks(2).map(|d| d[0] * 16 + d[1]).collect(),
+        _ => return Err(ParseColorError),
+    };
+
+    Ok(([bytes[0], bytes[1], bytes[2]], bytes.get(3).cloned()))
+}
+
+#[doc = "Color as hue, saturation and value of sRGB components."]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Hsv {
+    #[doc = "Hue in degrees, `0..360`."]
+    pub h: f32,
+
+    #[doc = "Saturation, `0..1`."]
+    pub s: f32,
+
+    #[doc = "Value, `0..1`."]
+    pub v: f32,
+}
+
+#[doc = "Color as hue, saturation and lightness of sRGB components."]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Hsl {
+    #[doc = "Hue in degrees, `0..360`."]
+    pub h: f32,
+
+    #[doc = "Saturation, `0..1`."]
+    pub s: f32,
+
+    #[doc = "Lightness, `0..1`."]
+    pub l: f32,
+}
+
+// Hue in degrees, maximum and minimum of components.
+fn hue(c: &Srgb) -> (f32, f32, f32) {
+    let max = c.r.max(c.g).max(c.b);
+    let min = c.r.min(c.g).min(c.b);
+    let d = max - min;
+
+    let h = if d <= 0.0 {
+        0.0
+    } else if max == c.r {
+        60.0 * ((c.g - c.b) / d).rem_euclid(6.0)
+    } else if max == c.g {
+        60.0 * ((c.b - c.r) / d + 2.0)
+    } else {
+        60.0 * ((c.r - c.g) / d + 4.0)
+    };
+
+    (h, max, min)
+}
+
+// Color from hue, chroma and minimum of components.
+fn from_hue(h: f32, chroma: f32, min: f32) -> Srgb {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    Srgb::new(r + min, g + min, b + min)
+}
+
+impl From<Srgb> for Hsv {
+    fn from(c: Srgb) -> Hsv {
+        let (h, max, min) = hue(&c);
+        let s = if max > 0.0 { (max - min) / max } else { 0.0 };
+
+        Hsv { h, s, v: max }
+    }
+}
+
+impl From<Hsv> for Srgb {
+    fn from(c: Hsv) -> Srgb {
+        let chroma = c.v * c.s;
+
+        from_hue(c.h, chroma, c.v - chroma)
+    }
+}
+
+impl From<Srgb> for Hsl {
+    fn from(c: Srgb) -> Hsl {
+        let (h, max, min) = hue(&c);
+        let l = (max + min) / 2.0;
+        let s = if max > min {
+            (max - min) / (1.0 - (2.0 * l - 1.0).abs())
+        } else {
+            0.0
+        };
+
+        Hsl { h, s, l }
+    }
+}
+
+impl From<Hsl> for Srgb {
+    fn from(c: Hsl) -> Srgb {
+        let chroma = (1.0 - (2.0 * c.l - 1.0).abs()) * c.s;
+
+        from_hue(c.h, chroma, c.l - chroma / 2.0)
+    }
+}
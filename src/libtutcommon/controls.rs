@@ -1,6 +1,8 @@
 #![doc = "Common stuff for controls."]
 
-use matrix::{Matrix4f, Vector3f};
+use matrix::{Matrix4f, Vector3f, Viewport};
+
+use sdl::{WINDOW_HEIGHT, WINDOW_WIDTH};
 
 use sdl2::{self, keyboard::Scancode, mouse::MouseWheelDirection};
 
@@ -14,6 +16,9 @@ pub struct Controls {
     #[doc = "View matrix"]
     pub view: Matrix4f,
 
+    #[doc = "Viewport of window"]
+    pub viewport: Viewport,
+
     position: Vector3f,
     horizontal_angle: f32,
     vertical_angle: f32,
@@ -47,6 +52,13 @@ impl Controls {
                 &Vector3f(0.0, 1.0, 0.0), /* Head is up (set to 0,-1,0 to
                                            * look upside-down) */
             ),
+            // Whole window.
+            viewport: Viewport {
+                x: 0,
+                y: 0,
+                width: WINDOW_WIDTH as i32,
+                height: WINDOW_HEIGHT as i32,
+            },
             radius: 2.0,
         }
     }
@@ -113,7 +125,7 @@ impl Controls {
         if keyboard_state.is_scancode_pressed(Scancode::Right) {
             self.position = &self.position + &(&right * (delta_time as f32 * self.speed));
         }*/
-        self.position = &direction * (-self.radius);
+        self.position = direction * (-self.radius);
 
         // Projection matrix : 45 deg; Field of View,
         // 4:3 ratio, display range : 0.1 unit <-> 100 units
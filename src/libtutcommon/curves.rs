@@ -0,0 +1,312 @@
+#![doc = "Spline curves for camera and object paths."]
+//! Curves are parameterized by time `t` in `0..1` over the whole curve, each
+//! segment gets equal part of it. Use `ArcLength` to move with constant
+//! speed.
+
+use matrix::{Quaternion, Vector3f};
+
+#[doc = "Value which can be interpolated along curve."]
+pub trait Interpolate: Copy {
+    #[doc = "Interpolate between values, `t` can be out of `0..1` range."]
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+
+    #[doc = "Velocity of `interpolate` from `self` to `other`."]
+    fn velocity(&self, other: &Self) -> Vector3f;
+
+    #[doc = "Move value with velocity for unit time."]
+    fn advance(&self, velocity: &Vector3f) -> Self;
+}
+
+impl Interpolate for Vector3f {
+    fn interpolate(&self, other: &Vector3f, t: f32) -> Vector3f {
+        self.lerp(other, t)
+    }
+
+    fn velocity(&self, other: &Vector3f) -> Vector3f {
+        other - self
+    }
+
+    fn advance(&self, velocity: &Vector3f) -> Vector3f {
+        self + velocity
+    }
+}
+
+// Velocity of rotation is angular velocity in radians.
+impl Interpolate for Quaternion {
+    fn interpolate(&self, other: &Quaternion, t: f32) -> Quaternion {
+        self.slerp(other, t)
+    }
+
+    fn velocity(&self, other: &Quaternion) -> Vector3f {
+        let d = other.mul(&self.conjugate());
+        // Slerp follows the shortest path.
+        let d = if d.w < 0.0 {
+            Quaternion {
+                x: -d.x,
+                y: -d.y,
+                z: -d.z,
+                w: -d.w,
+            }
+        } else {
+            d
+        };
+
+        let (axis, angle) = d.to_axis_angle();
+        axis * angle.to_radians()
+    }
+
+    fn advance(&self, velocity: &Vector3f) -> Quaternion {
+        let angle = velocity.length();
+        if angle < f32::EPSILON {
+            return *self;
+        }
+
+        Quaternion::from_axis_angle(angle.to_degrees(), velocity).mul(self)
+    }
+}
+
+#[doc = "Curve evaluated at time in `0..1` range."]
+pub trait Curve {
+    #[doc = "Type of curve points."]
+    type Point: Interpolate;
+
+    #[doc = "Point at time `t`, it's clamped to `0..1`."]
+    fn position(&self, t: f32) -> Self::Point;
+
+    #[doc = "Derivative by time at `t`."]
+    /// It's angular velocity in radians for rotations.
+    fn tangent(&self, t: f32) -> Vector3f;
+}
+
+// Segment index and time inside segment.
+fn segment(t: f32, count: usize) -> (usize, f32) {
+    let x = t.clamp(0.0, 1.0) * count as f32;
+    let i = (x as usize).min(count - 1);
+
+    (i, x - i as f32)
+}
+
+#[doc = "Piecewise linear curve through points."]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Linear<P> {
+    points: Vec<P>,
+}
+
+impl<P: Interpolate> Linear<P> {
+    #[doc = "Create curve through points."]
+    /// Returns `None` if there are less than 2 points.
+    pub fn new(points: Vec<P>) -> Option<Linear<P>> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        Some(Linear { points })
+    }
+
+    #[doc = "Points of curve."]
+    pub fn points(&self) -> &[P] {
+        &self.points
+    }
+}
+
+impl<P: Interpolate> Curve for Linear<P> {
+    type Point = P;
+
+    fn position(&self, t: f32) -> P {
+        let (i, u) = segment(t, self.points.len() - 1);
+
+        self.points[i].interpolate(&self.points[i + 1], u)
+    }
+
+    fn tangent(&self, t: f32) -> Vector3f {
+        let count = self.points.len() - 1;
+        let (i, _) = segment(t, count);
+
+        self.points[i].velocity(&self.points[i + 1]) * count as f32
+    }
+}
+
+#[doc = "Piecewise cubic curve, each segment is cubic Bezier curve."]
+/// Catmull-Rom and Hermite splines are converted to Bezier segments. Points
+/// are evaluated with de Casteljau algorithm, so rotations are interpolated
+/// with slerp. Tangents of rotations are exact at segment ends only.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubicSpline<P> {
+    segments: Vec<[P; 4]>,
+}
+
+impl<P: Interpolate> CubicSpline<P> {
+    #[doc = "Bezier curve from end points and control points."]
+    /// Segments share end points, so there should be `3 * n + 1` points for
+    /// `n` segments. Returns `None` for wrong number of points.
+    pub fn bezier(points: &[P]) -> Option<CubicSpline<P>> {
+        if points.len() < 4 || points.len() % 3 != 1 {
+            return None;
+        }
+
+        let segments = points
+            .windows(4)
+            .step_by(3)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+            .collect();
+
+        Some(CubicSpline { segments })
+    }
+
+    #[doc = "Hermite curve through points with tangents in them."]
+    /// Tangents are derivatives by time of each segment. Returns `None` if
+    /// there are less than 2 points or numbers of points and tangents differ.
+    pub fn hermite(points: &[P], tangents: &[Vector3f]) -> Option<CubicSpline<P>> {
+        if points.len() < 2 || points.len() != tangents.len() {
+            return None;
+        }
+
+        let segments = (0..points.len() - 1)
+            .map(|i| {
+                let (p0, p1) = (points[i], points[i + 1]);
+                [
+                    p0,
+                    p0.advance(&(tangents[i] / 3.0)),
+                    p1.advance(&(tangents[i + 1] / -3.0)),
+                    p1,
+                ]
+            })
+            .collect();
+
+        Some(CubicSpline { segments })
+    }
+
+    #[doc = "Uniform Catmull-Rom curve through points."]
+    /// Tangent in each point is half of velocity between neighbour points,
+    /// end points are duplicated. Returns `None` if there are less than 2
+    /// points.
+    pub fn catmull_rom(points: &[P]) -> Option<CubicSpline<P>> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        let last = points.len() - 1;
+        let tangents: Vec<Vector3f> = (0..points.len())
+            .map(|i| {
+                let prev = points[i.saturating_sub(1)];
+                let next = points[(i + 1).min(last)];
+
+                prev.velocity(&next) / 2.0
+            })
+            .collect();
+
+        CubicSpline::hermite(points, &tangents)
+    }
+
+    #[doc = "Bezier segments of curve."]
+    pub fn segments(&self) -> &[[P; 4]] {
+        &self.segments
+    }
+
+    // Points of the last but one step of de Casteljau algorithm.
+    fn casteljau(&self, t: f32) -> (P, P, f32) {
+        let (i, u) = segment(t, self.segments.len());
+        let p = &self.segments[i];
+
+        let a = p[0].interpolate(&p[1], u);
+        let b = p[1].interpolate(&p[2], u);
+        let c = p[2].interpolate(&p[3], u);
+
+        (a.interpolate(&b, u), b.interpolate(&c, u), u)
+    }
+}
+
+impl<P: Interpolate> Curve for CubicSpline<P> {
+    type Point = P;
+
+    fn position(&self, t: f32) -> P {
+        let (a, b, u) = self.casteljau(t);
+
+        a.interpolate(&b, u)
+    }
+
+    fn tangent(&self, t: f32) -> Vector3f {
+        let (a, b, _) = self.casteljau(t);
+
+        a.velocity(&b) * (3.0 * self.segments.len() as f32)
+    }
+}
+
+#[doc = "Curve reparameterized by arc length."]
+/// Time `0..1` is proportional to distance along curve, so points move with
+/// constant speed. Length is approximated by polyline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArcLength<C> {
+    curve: C,
+    distances: Vec<f32>,
+}
+
+impl<C: Curve<Point = Vector3f>> ArcLength<C> {
+    #[doc = "Reparameterize curve, `samples` is number of polyline segments."]
+    pub fn new(curve: C, samples: usize) -> ArcLength<C> {
+        let samples = samples.max(1);
+        let mut distances = Vec::with_capacity(samples + 1);
+
+        let mut prev = curve.position(0.0);
+        let mut length = 0.0;
+        distances.push(length);
+        for i in 1..=samples {
+            let p = curve.position(i as f32 / samples as f32);
+            length += p.distance(&prev);
+            distances.push(length);
+            prev = p;
+        }
+
+        ArcLength { curve, distances }
+    }
+
+    #[doc = "Original curve."]
+    pub fn curve(&self) -> &C {
+        &self.curve
+    }
+
+    #[doc = "Length of curve."]
+    pub fn length(&self) -> f32 {
+        self.distances[self.distances.len() - 1]
+    }
+
+    #[doc = "Time of original curve at distance from its start."]
+    pub fn time_at_distance(&self, distance: f32) -> f32 {
+        let samples = self.distances.len() - 1;
+        let distance = distance.clamp(0.0, self.length());
+
+        // First sample not before distance.
+        let i = self
+            .distances
+            .partition_point(|&d| d < distance)
+            .clamp(1, samples);
+        let (d0, d1) = (self.distances[i - 1], self.distances[i]);
+        let u = if d1 > d0 {
+            (distance - d0) / (d1 - d0)
+        } else {
+            0.0
+        };
+
+        (i as f32 - 1.0 + u) / samples as f32
+    }
+}
+
+impl<C: Curve<Point = Vector3f>> Curve for ArcLength<C> {
+    type Point = Vector3f;
+
+    fn position(&self, t: f32) -> Vector3f {
+        self.curve
+            .position(self.time_at_distance(t * self.length()))
+    }
+
+    fn tangent(&self, t: f32) -> Vector3f {
+        let tangent = self.curve.tangent(self.time_at_distance(t * self.length()));
+        let speed = tangent.length();
+
+        if speed < f32::EPSILON {
+            tangent
+        } else {
+            tangent * (self.length() / speed)
+        }
+    }
+}
@@ -0,0 +1,583 @@
+#![doc = "Geometric primitives."]
+
+use controls::Controls;
+
+use matrix::{self, Matrix4f, Vector3f, Vector4f, Viewport};
+
+#[doc = "Result of intersection test."]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    #[doc = "Distance along ray, or penetration depth for volumes."]
+    pub distance: f32,
+
+    #[doc = "Surface normal at hit point, or direction to push the other volume out."]
+    pub normal: Vector3f,
+}
+
+#[doc = "Half-line with origin and direction."]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    #[doc = "Start point."]
+    pub origin: Vector3f,
+
+    #[doc = "Direction of length 1."]
+    pub direction: Vector3f,
+}
+
+impl Ray {
+    #[doc = "Create ray, direction is normalized."]
+    pub fn new(origin: Vector3f, direction: Vector3f) -> Ray {
+        Ray {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    #[doc = "Point at distance t from origin."]
+    pub fn point_at(&self, t: f32) -> Vector3f {
+        self.origin + self.direction * t
+    }
+
+    #[doc = "Nearest intersection with plane in front of ray."]
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<Hit> {
+        let denom = plane.normal.dot(&self.direction);
+        if denom.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let t = -plane.signed_distance(&self.origin) / denom;
+        if t < 0.0 {
+            return None;
+        }
+
+        Some(Hit {
+            distance: t,
+            normal: plane.normal,
+        })
+    }
+
+    #[doc = "Intersection with triangle, Möller–Trumbore algorithm."]
+    /// Both sides of triangle are hit, normal is the face normal for
+    /// counter-clockwise winding.
+    pub fn intersect_triangle(&self, triangle: &Triangle) -> Option<Hit> {
+        let edge1 = triangle.b - triangle.a;
+        let edge2 = triangle.c - triangle.a;
+
+        let p = self.direction.cross(&edge2);
+        let det = edge1.dot(&p);
+        // Ray is parallel to triangle.
+        if det.abs() < f32::EPSILON {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let s = self.origin - triangle.a;
+        let u = s.dot(&p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = s.cross(&edge1);
+        let v = self.direction.dot(&q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(&q) * inv_det;
+        if t < 0.0 {
+            return None;
+        }
+
+        Some(Hit {
+            distance: t,
+            normal: edge1.cross(&edge2).normalize(),
+        })
+    }
+
+    #[doc = "Nearest intersection with triangle list."]
+    /// Every 3 vertices form a triangle, like positions of `Mesh` without
+    /// indices.
+    pub fn intersect_triangles(&self, vertices: &[Vector3f]) -> Option<Hit> {
+        vertices
+            .chunks(3)
+            .filter(|t| t.len() == 3)
+            .filter_map(|t| {
+                self.intersect_triangle(&Triangle {
+                    a: t[0],
+                    b: t[1],
+                    c: t[2],
+                })
+            })
+            .fold(None, |nearest: Option<Hit>, hit| match nearest {
+                Some(n) if n.distance <= hit.distance => Some(n),
+                _ => Some(hit),
+            })
+    }
+
+    #[doc = "Intersection with axis-aligned box, slab method."]
+    /// If ray starts inside box, exit point is returned. Normal points out of
+    /// box.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<Hit> {
+        let mut t_near = f32::NEG_INFINITY;
+        let mut t_far = f32::INFINITY;
+        let mut near_axis = 0;
+        let mut far_axis = 0;
+
+        for axis in 0..3 {
+            let o = self.origin[axis];
+            let d = self.direction[axis];
+            let (min, max) = (aabb.min[axis], aabb.max[axis]);
+
+            if d.abs() < f32::EPSILON {
+                // Ray is parallel to slab.
+                if o < min || o > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let t1 = (min - o) / d;
+            let t2 = (max - o) / d;
+            let (t1, t2) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+
+            if t1 > t_near {
+                t_near = t1;
+                near_axis = axis;
+            }
+            if t2 < t_far {
+                t_far = t2;
+                far_axis = axis;
+            }
+            if t_near > t_far || t_far < 0.0 {
+                return None;
+            }
+        }
+
+        let entry = t_near >= 0.0;
+        let (t, axis) = if entry {
+            (t_near, near_axis)
+        } else {
+            (t_far, far_axis)
+        };
+
+        // Normal points against ray on entry and along ray on exit.
+        let mut normal = Vector3f::default();
+        normal[axis] = if (self.direction[axis] < 0.0) == entry {
+            1.0
+        } else {
+            -1.0
+        };
+
+        Some(Hit {
+            distance: t,
+            normal,
+        })
+    }
+
+    #[doc = "Intersection with oriented box."]
+    /// Box is `aabb` in model space transformed by `model` matrix. Distance is
+    /// measured in world space.
+    pub fn intersect_obb(&self, aabb: &Aabb, model: &Matrix4f) -> Option<Hit> {
+        let inv = model.inverse()?;
+
+        // Direction isn't normalized, so distance along local ray is the same
+        // as along world ray.
+        let local = Ray {
+            origin: inv.transform_point(&self.origin),
+            direction: inv.transform_direction(&self.direction),
+        };
+        let hit = local.intersect_aabb(aabb)?;
+
+        Some(Hit {
+            distance: hit.distance,
+            normal: (&model.normal_matrix()? * hit.normal).normalize(),
+        })
+    }
+
+    #[doc = "Nearest intersection with sphere."]
+    /// If ray starts inside sphere, exit point is returned.
+    pub fn intersect_sphere(&self, sphere: &BoundingSphere) -> Option<Hit> {
+        let oc = self.origin - sphere.center;
+        let b = oc.dot(&self.direction);
+        let c = oc.length_squared() - sphere.radius * sphere.radius;
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let sqrt_d = discriminant.sqrt();
+        let t = if -b - sqrt_d >= 0.0 {
+            -b - sqrt_d
+        } else {
+            -b + sqrt_d
+        };
+        if t < 0.0 {
+            return None;
+        }
+
+        Some(Hit {
+            distance: t,
+            normal: (self.point_at(t) - sphere.center).normalize(),
+        })
+    }
+
+    #[doc = "Ray from near to far plane through window point."]
+    /// `x` and `y` are in OpenGL window coordinates with origin at lower left
+    /// corner, `view_projection` is projection matrix multiplied by view
+    /// matrix. Returns `None` if `view_projection` is singular.
+    pub fn unproject(
+        x: f32,
+        y: f32,
+        view_projection: &Matrix4f,
+        viewport: &Viewport,
+    ) -> Option<Ray> {
+        let near = matrix::unproject(&Vector3f(x, y, 0.0), view_projection, viewport)?;
+        let far = matrix::unproject(&Vector3f(x, y, 1.0), view_projection, viewport)?;
+
+        Some(Ray::new(near, far - near))
+    }
+
+    #[doc = "Ray through center of pixel with origin at upper left corner."]
+    /// `x` and `y` are window coordinates like in SDL mouse events, viewport
+    /// should cover whole height of window.
+    pub fn from_pixel(
+        x: i32,
+        y: i32,
+        view_projection: &Matrix4f,
+        viewport: &Viewport,
+    ) -> Option<Ray> {
+        Ray::unproject(
+            x as f32 + 0.5,
+            viewport.height as f32 - y as f32 - 0.5,
+            view_projection,
+            viewport,
+        )
+    }
+
+    #[doc = "World space ray under mouse cursor."]
+    /// `x` and `y` are taken from SDL mouse event with origin at upper left
+    /// corner of window.
+    pub fn from_screen(x: i32, y: i32, controls: &Controls) -> Option<Ray> {
+        Ray::from_pixel(
+            x,
+            y,
+            &controls.projection.mul(&controls.view),
+            &controls.viewport,
+        )
+    }
+}
+
+#[doc = "Axis-aligned bounding box."]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    #[doc = "Minimal corner."]
+    pub min: Vector3f,
+
+    #[doc = "Maximal corner."]
+    pub max: Vector3f,
+}
+
+impl Aabb {
+    #[doc = "Create box from corners in any order."]
+    pub fn new(a: Vector3f, b: Vector3f) -> Aabb {
+        Aabb {
+            min: a.min(&b),
+            max: a.max(&b),
+        }
+    }
+
+    #[doc = "Smallest box containing all points."]
+    /// Returns `None` for empty slice.
+    pub fn from_points(points: &[Vector3f]) -> Option<Aabb> {
+        let first = points.first()?;
+
+        Some(points.iter().fold(
+            Aabb {
+                min: *first,
+                max: *first,
+            },
+            |aabb, p| Aabb {
+                min: aabb.min.min(p),
+                max: aabb.max.max(p),
+            },
+        ))
+    }
+
+    #[doc = "Center of box."]
+    pub fn center(&self) -> Vector3f {
+        (self.min + self.max) * 0.5
+    }
+
+    #[doc = "Size of box along each axis."]
+    pub fn size(&self) -> Vector3f {
+        self.max - self.min
+    }
+
+    #[doc = "Check if point is inside box or on its boundary."]
+    pub fn contains_point(&self, p: &Vector3f) -> bool {
+        (0..3).all(|i| p[i] >= self.min[i] && p[i] <= self.max[i])
+    }
+
+    #[doc = "Box containing this box transformed by matrix."]
+    pub fn transform(&self, m: &Matrix4f) -> Aabb {
+        let corners: Vec<Vector3f> = (0..8)
+            .map(|i| {
+                m.transform_point(&Vector3f(
+                    if i & 1 == 0 { self.min.0 } else { self.max.0 },
+                    if i & 2 == 0 { self.min.1 } else { self.max.1 },
+                    if i & 4 == 0 { self.min.2 } else { self.max.2 },
+                ))
+            })
+            .collect();
+
+        Aabb::from_points(&corners).unwrap()
+    }
+
+    #[doc = "Overlap with other box."]
+    /// Distance is penetration depth along the axis of the smallest overlap,
+    /// normal points from this box to `other`.
+    pub fn intersect_aabb(&self, other: &Aabb) -> Option<Hit> {
+        let mut hit: Option<Hit> = None;
+
+        for axis in 0..3 {
+            let overlap = self.max[axis].min(other.max[axis]) - self.min[axis].max(other.min[axis]);
+            if overlap < 0.0 {
+                return None;
+            }
+
+            if hit.is_none_or(|h| overlap < h.distance) {
+                let mut normal = Vector3f::default();
+                normal[axis] = if other.center()[axis] < self.center()[axis] {
+                    -1.0
+                } else {
+                    1.0
+                };
+                hit = Some(Hit {
+                    distance: overlap,
+                    normal,
+                });
+            }
+        }
+
+        hit
+    }
+}
+
+#[doc = "Bounding sphere."]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingSphere {
+    #[doc = "Center of sphere."]
+    pub center: Vector3f,
+
+    #[doc = "Radius of sphere."]
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    #[doc = "Sphere around bounding box of points."]
+    /// Not the smallest possible, but cheap. Returns `None` for empty slice.
+    pub fn from_points(points: &[Vector3f]) -> Option<BoundingSphere> {
+        let center = Aabb::from_points(points)?.center();
+        let radius = points
+            .iter()
+            .map(|p| p.distance(&center))
+            .fold(0.0, f32::max);
+
+        Some(BoundingSphere { center, radius })
+    }
+
+    #[doc = "Check if point is inside sphere or on its boundary."]
+    pub fn contains_point(&self, p: &Vector3f) -> bool {
+        p.distance(&self.center) <= self.radius
+    }
+
+    #[doc = "Overlap with other sphere."]
+    /// Distance is penetration depth, normal points from this sphere to
+    /// `other`.
+    pub fn intersect_sphere(&self, other: &BoundingSphere) -> Option<Hit> {
+        let d = other.center - self.center;
+        let l = d.length();
+        let depth = self.radius + other.radius - l;
+        if depth < 0.0 {
+            return None;
+        }
+
+        Some(Hit {
+            distance: depth,
+            normal: if l > f32::EPSILON {
+                d / l
+            } else {
+                Vector3f(0.0, 1.0, 0.0)
+            },
+        })
+    }
+}
+
+#[doc = "Plane of points p where normal.dot(p) + distance is 0."]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    #[doc = "Normal of length 1."]
+    pub normal: Vector3f,
+
+    #[doc = "Signed distance from origin along negative normal."]
+    pub distance: f32,
+}
+
+impl Plane {
+    #[doc = "Plane through point with normal."]
+    pub fn from_point_normal(point: &Vector3f, normal: &Vector3f) -> Plane {
+        let normal = normal.normalize();
+
+        Plane {
+            normal,
+            distance: -normal.dot(point),
+        }
+    }
+
+    #[doc = "Plane through 3 points, counter-clockwise order faces normal."]
+    pub fn from_points(a: &Vector3f, b: &Vector3f, c: &Vector3f) -> Plane {
+        Plane::from_point_normal(a, &(b - a).cross(&(c - a)))
+    }
+
+    #[doc = "Signed distance from plane, positive on the normal side."]
+    pub fn signed_distance(&self, p: &Vector3f) -> f32 {
+        self.normal.dot(p) + self.distance
+    }
+}
+
+#[doc = "Triangle with counter-clockwise vertices."]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Triangle {
+    #[doc = "First vertex."]
+    pub a: Vector3f,
+
+    #[doc = "Second vertex."]
+    pub b: Vector3f,
+
+    #[doc = "Third vertex."]
+    pub c: Vector3f,
+}
+
+impl Triangle {
+    #[doc = "Face normal."]
+    pub fn normal(&self) -> Vector3f {
+        (self.b - self.a).cross(&(self.c - self.a)).normalize()
+    }
+
+    #[doc = "Plane of triangle."]
+    pub fn plane(&self) -> Plane {
+        Plane::from_points(&self.a, &self.b, &self.c)
+    }
+}
+
+#[doc = "Position of volume relative to frustum."]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Containment {
+    #[doc = "Completely inside."]
+    Inside,
+
+    #[doc = "Completely outside."]
+    Outside,
+
+    #[doc = "Crosses the boundary."]
+    Intersecting,
+}
+
+#[doc = "View frustum for culling."]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    #[doc = "Left, right, bottom, top, near and far planes with normals inside."]
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    #[doc = "Extract clip planes from matrix."]
+    /// For `projection.mul(&view)` planes are in world space, for
+    /// `projection.mul(&view).mul(&model)` they are in model space.
+    pub fn from_matrix(m: &Matrix4f) -> Frustum {
+        let r0 = m.row(0);
+        let r1 = m.row(1);
+        let r2 = m.row(2);
+        let r3 = m.row(3);
+
+        // Point is inside if -w <= x, y, z <= w in clip space.
+        let plane = |v: Vector4f| {
+            let l = v.truncate().length();
+            Plane {
+                normal: v.truncate() / l,
+                distance: v.3 / l,
+            }
+        };
+
+        Frustum {
+            planes: [
+                plane(r3 + r0),
+                plane(r3 - r0),
+                plane(r3 + r1),
+                plane(r3 - r1),
+                plane(r3 + r2),
+                plane(r3 - r2),
+            ],
+        }
+    }
+
+    #[doc = "Check point, points on the boundary are intersecting."]
+    pub fn contains_point(&self, p: &Vector3f) -> Containment {
+        let mut res = Containment::Inside;
+
+        for plane in self.planes.iter() {
+            let d = plane.signed_distance(p);
+            if d < 0.0 {
+                return Containment::Outside;
+            }
+            if d == 0.0 {
+                res = Containment::Intersecting;
+            }
+        }
+
+        res
+    }
+
+    #[doc = "Check axis-aligned box."]
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> Containment {
+        let mut res = Containment::Inside;
+
+        for plane in self.planes.iter() {
+            // Corners farthest along and against the normal.
+            let mut positive = aabb.min;
+            let mut negative = aabb.max;
+            for i in 0..3 {
+                if plane.normal[i] >= 0.0 {
+                    positive[i] = aabb.max[i];
+                    negative[i] = aabb.min[i];
+                }
+            }
+
+            if plane.signed_distance(&positive) < 0.0 {
+                return Containment::Outside;
+            }
+            if plane.signed_distance(&negative) < 0.0 {
+                res = Containment::Intersecting;
+            }
+        }
+
+        res
+    }
+
+    #[doc = "Check sphere."]
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> Containment {
+        let mut res = Containment::Inside;
+
+        for plane in self.planes.iter() {
+            let d = plane.signed_distance(&sphere.center);
+            if d < -sphere.radius {
+                return Containment::Outside;
+            }
+            if d < sphere.radius {
+                res = Containment::Intersecting;
+            }
+        }
+
+        res
+    }
+}
@@ -0,0 +1,364 @@
+// Conversions between math types of tutcommon and other crates. Each crate is
+// enabled by cargo feature with the same name. All matrices are column major,
+// so columns are copied as is.
+
+#[cfg(feature = "mint")]
+mod mint_impls {
+    use matrix::{Matrix3, Matrix4, Quaternion, Scalar, Vector2, Vector3, Vector4};
+    use mint;
+
+    // Vector converted from/into mint vector with the same components.
+    macro_rules! impl_mint_vector {
+        ($V:ident, $($f:tt => $m:ident),+) => {
+            impl<T: Scalar> From<$V<T>> for mint::$V<T> {
+                fn from(v: $V<T>) -> mint::$V<T> {
+                    mint::$V { $($m: v.$f),+ }
+                }
+            }
+
+            impl<T: Scalar> From<mint::$V<T>> for $V<T> {
+                fn from(v: mint::$V<T>) -> $V<T> {
+                    $V($(v.$m),+)
+                }
+            }
+        };
+    }
+
+    impl_mint_vector!(Vector2, 0 => x, 1 => y);
+    impl_mint_vector!(Vector3, 0 => x, 1 => y, 2 => z);
+    impl_mint_vector!(Vector4, 0 => x, 1 => y, 2 => z, 3 => w);
+
+    impl<T: Scalar> From<Matrix3<T>> for mint::ColumnMatrix3<T> {
+        fn from(m: Matrix3<T>) -> mint::ColumnMatrix3<T> {
+            mint::ColumnMatrix3::from(*m.columns())
+        }
+    }
+
+    impl<T: Scalar> From<mint::ColumnMatrix3<T>> for Matrix3<T> {
+        fn from(m: mint::ColumnMatrix3<T>) -> Matrix3<T> {
+            Matrix3::from_columns(m.into())
+        }
+    }
+
+    impl<T: Scalar> From<Matrix4<T>> for mint::ColumnMatrix4<T> {
+        fn from(m: Matrix4<T>) -> mint::ColumnMatrix4<T> {
+            mint::ColumnMatrix4::from(*m.columns())
+        }
+    }
+
+    impl<T: Scalar> From<mint::ColumnMatrix4<T>> for Matrix4<T> {
+        fn from(m: mint::ColumnMatrix4<T>) -> Matrix4<T> {
+            Matrix4::from_columns(m.into())
+        }
+    }
+
+    impl<T: Scalar> From<Quaternion<T>> for mint::Quaternion<T> {
+        fn from(q: Quaternion<T>) -> mint::Quaternion<T> {
+            mint::Quaternion {
+                v: mint::Vector3 {
+                    x: q.x,
+                    y: q.y,
+                    z: q.z,
+                },
+                s: q.w,
+            }
+        }
+    }
+
+    impl<T: Scalar> From<mint::Quaternion<T>> for Quaternion<T> {
+        fn from(q: mint::Quaternion<T>) -> Quaternion<T> {
+            Quaternion {
+                x: q.v.x,
+                y: q.v.y,
+                z: q.v.z,
+                w: q.s,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+mod glam_impls {
+    use glam;
+    use matrix::{Matrix3, Matrix4, Quaternion, Vector2, Vector3, Vector4};
+
+    // Conversions for glam types of one precision.
+    macro_rules! impl_glam {
+        ($T:ident, $Vec2:ident, $Vec3:ident, $Vec4:ident, $Mat3:ident, $Mat4:ident, $Quat:ident) => {
+            impl From<Vector2<$T>> for glam::$Vec2 {
+                fn from(v: Vector2<$T>) -> glam::$Vec2 {
+                    glam::$Vec2::new(v.0, v.1)
+                }
+            }
+
+            impl From<glam::$Vec2> for Vector2<$T> {
+                fn from(v: glam::$Vec2) -> Vector2<$T> {
+                    Vector2(v.x, v.y)
+                }
+            }
+
+            impl From<Vector3<$T>> for glam::$Vec3 {
+                fn from(v: Vector3<$T>) -> glam::$Vec3 {
+                    glam::$Vec3::new(v.0, v.1, v.2)
+                }
+            }
+
+            impl From<glam::$Vec3> for Vector3<$T> {
+                fn from(v: glam::$Vec3) -> Vector3<$T> {
+                    Vector3(v.x, v.y, v.z)
+                }
+            }
+
+            impl From<Vector4<$T>> for glam::$Vec4 {
+                fn from(v: Vector4<$T>) -> glam::$Vec4 {
+                    glam::$Vec4::new(v.0, v.1, v.2, v.3)
+                }
+            }
+
+            impl From<glam::$Vec4> for Vector4<$T> {
+                fn from(v: glam::$Vec4) -> Vector4<$T> {
+                    Vector4(v.x, v.y, v.z, v.w)
+                }
+            }
+
+            impl From<Matrix3<$T>> for glam::$Mat3 {
+                fn from(m: Matrix3<$T>) -> glam::$Mat3 {
+                    glam::$Mat3::from_cols_array_2d(m.columns())
+                }
+            }
+
+            impl From<glam::$Mat3> for Matrix3<$T> {
+                fn from(m: glam::$Mat3) -> Matrix3<$T> {
+                    Matrix3::from_columns(m.to_cols_array_2d())
+                }
+            }
+
+            impl From<Matrix4<$T>> for glam::$Mat4 {
+                fn from(m: Matrix4<$T>) -> glam::$Mat4 {
+                    glam::$Mat4::from_cols_array_2d(m.columns())
+                }
+            }
+
+            impl From<glam::$Mat4> for Matrix4<$T> {
+                fn from(m: glam::$Mat4) -> Matrix4<$T> {
+                    Matrix4::from_columns(m.to_cols_array_2d())
+                }
+            }
+
+            impl From<Quaternion<$T>> for glam::$Quat {
+                fn from(q: Quaternion<$T>) -> glam::$Quat {
+                    glam::$Quat::from_xyzw(q.x, q.y, q.z, q.w)
+                }
+            }
+
+            impl From<glam::$Quat> for Quaternion<$T> {
+                fn from(q: glam::$Quat) -> Quaternion<$T> {
+                    Quaternion {
+                        x: q.x,
+                        y: q.y,
+                        z: q.z,
+                        w: q.w,
+                    }
+                }
+            }
+        };
+    }
+
+    impl_glam!(f32, Vec2, Vec3, Vec4, Mat3, Mat4, Quat);
+    impl_glam!(f64, DVec2, DVec3, DVec4, DMat3, DMat4, DQuat);
+}
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impls {
+    use matrix::{Matrix3, Matrix4, Quaternion, Vector2, Vector3, Vector4};
+    use nalgebra;
+
+    // Conversions for nalgebra types of one precision.
+    macro_rules! impl_nalgebra {
+        ($T:ident) => {
+            impl From<Vector2<$T>> for nalgebra::Vector2<$T> {
+                fn from(v: Vector2<$T>) -> nalgebra::Vector2<$T> {
+                    nalgebra::Vector2::new(v.0, v.1)
+                }
+            }
+
+            impl From<nalgebra::Vector2<$T>> for Vector2<$T> {
+                fn from(v: nalgebra::Vector2<$T>) -> Vector2<$T> {
+                    Vector2(v[0], v[1])
+                }
+            }
+
+            impl From<Vector3<$T>> for nalgebra::Vector3<$T> {
+                fn from(v: Vector3<$T>) -> nalgebra::Vector3<$T> {
+                    nalgebra::Vector3::new(v.0, v.1, v.2)
+                }
+            }
+
+            impl From<nalgebra::Vector3<$T>> for Vector3<$T> {
+                fn from(v: nalgebra::Vector3<$T>) -> Vector3<$T> {
+                    Vector3(v[0], v[1], v[2])
+                }
+            }
+
+            impl From<Vector4<$T>> for nalgebra::Vector4<$T> {
+                fn from(v: Vector4<$T>) -> nalgebra::Vector4<$T> {
+                    nalgebra::Vector4::new(v.0, v.1, v.2, v.3)
+                }
+            }
+
+            impl From<nalgebra::Vector4<$T>> for Vector4<$T> {
+                fn from(v: nalgebra::Vector4<$T>) -> Vector4<$T> {
+                    Vector4(v[0], v[1], v[2], v[3])
+                }
+            }
+
+            impl From<Matrix3<$T>> for nalgebra::Matrix3<$T> {
+                fn from(m: Matrix3<$T>) -> nalgebra::Matrix3<$T> {
+                    nalgebra::Matrix3::from_fn(|i, j| m.columns()[j][i])
+                }
+            }
+
+            impl From<nalgebra::Matrix3<$T>> for Matrix3<$T> {
+                fn from(m: nalgebra::Matrix3<$T>) -> Matrix3<$T> {
+                    Matrix3::from_columns(m.into())
+                }
+            }
+
+            impl From<Matrix4<$T>> for nalgebra::Matrix4<$T> {
+                fn from(m: Matrix4<$T>) -> nalgebra::Matrix4<$T> {
+                    nalgebra::Matrix4::from_fn(|i, j| m.columns()[j][i])
+                }
+            }
+
+            impl From<nalgebra::Matrix4<$T>> for Matrix4<$T> {
+                fn from(m: nalgebra::Matrix4<$T>) -> Matrix4<$T> {
+                    Matrix4::from_columns(m.into())
+                }
+            }
+
+            impl From<Quaternion<$T>> for nalgebra::Quaternion<$T> {
+                fn from(q: Quaternion<$T>) -> nalgebra::Quaternion<$T> {
+                    nalgebra::Quaternion::new(q.w, q.x, q.y, q.z)
+                }
+            }
+
+            impl From<nalgebra::Quaternion<$T>> for Quaternion<$T> {
+                fn from(q: nalgebra::Quaternion<$T>) -> Quaternion<$T> {
+                    Quaternion {
+                        x: q.i,
+                        y: q.j,
+                        z: q.k,
+                        w: q.w,
+                    }
+                }
+            }
+
+            // Rotation is normalized by nalgebra.
+            impl From<Quaternion<$T>> for nalgebra::UnitQuaternion<$T> {
+                fn from(q: Quaternion<$T>) -> nalgebra::UnitQuaternion<$T> {
+                    nalgebra::UnitQuaternion::from_quaternion(q.into())
+                }
+            }
+
+            impl From<nalgebra::UnitQuaternion<$T>> for Quaternion<$T> {
+                fn from(q: nalgebra::UnitQuaternion<$T>) -> Quaternion<$T> {
+                    q.into_inner().into()
+                }
+            }
+        };
+    }
+
+    impl_nalgebra!(f32);
+    impl_nalgebra!(f64);
+}
+
+#[cfg(feature = "cgmath")]
+mod cgmath_impls {
+    use cgmath;
+    use matrix::{Matrix3, Matrix4, Quaternion, Vector2, Vector3, Vector4};
+
+    // Conversions for cgmath types of one precision.
+    macro_rules! impl_cgmath {
+        ($T:ident) => {
+            impl From<Vector2<$T>> for cgmath::Vector2<$T> {
+                fn from(v: Vector2<$T>) -> cgmath::Vector2<$T> {
+                    cgmath::Vector2::new(v.0, v.1)
+                }
+            }
+
+            impl From<cgmath::Vector2<$T>> for Vector2<$T> {
+                fn from(v: cgmath::Vector2<$T>) -> Vector2<$T> {
+                    Vector2(v.x, v.y)
+                }
+            }
+
+            impl From<Vector3<$T>> for cgmath::Vector3<$T> {
+                fn from(v: Vector3<$T>) -> cgmath::Vector3<$T> {
+                    cgmath::Vector3::new(v.0, v.1, v.2)
+                }
+            }
+
+            impl From<cgmath::Vector3<$T>> for Vector3<$T> {
+                fn from(v: cgmath::Vector3<$T>) -> Vector3<$T> {
+                    Vector3(v.x, v.y, v.z)
+                }
+            }
+
+            impl From<Vector4<$T>> for cgmath::Vector4<$T> {
+                fn from(v: Vector4<$T>) -> cgmath::Vector4<$T> {
+                    cgmath::Vector4::new(v.0, v.1, v.2, v.3)
+                }
+            }
+
+            impl From<cgmath::Vector4<$T>> for Vector4<$T> {
+                fn from(v: cgmath::Vector4<$T>) -> Vector4<$T> {
+                    Vector4(v.x, v.y, v.z, v.w)
+                }
+            }
+
+            impl From<Matrix3<$T>> for cgmath::Matrix3<$T> {
+                fn from(m: Matrix3<$T>) -> cgmath::Matrix3<$T> {
+                    cgmath::Matrix3::from(*m.columns())
+                }
+            }
+
+            impl From<cgmath::Matrix3<$T>> for Matrix3<$T> {
+                fn from(m: cgmath::Matrix3<$T>) -> Matrix3<$T> {
+                    Matrix3::from_columns(m.into())
+                }
+            }
+
+            impl From<Matrix4<$T>> for cgmath::Matrix4<$T> {
+                fn from(m: Matrix4<$T>) -> cgmath::Matrix4<$T> {
+                    cgmath::Matrix4::from(*m.columns())
+                }
+            }
+
+            impl From<cgmath::Matrix4<$T>> for Matrix4<$T> {
+                fn from(m: cgmath::Matrix4<$T>) -> Matrix4<$T> {
+                    Matrix4::from_columns(m.into())
+                }
+            }
+
+            impl From<Quaternion<$T>> for cgmath::Quaternion<$T> {
+                fn from(q: Quaternion<$T>) -> cgmath::Quaternion<$T> {
+                    cgmath::Quaternion::new(q.w, q.x, q.y, q.z)
+                }
+            }
+
+            impl From<cgmath::Quaternion<$T>> for Quaternion<$T> {
+                fn from(q: cgmath::Quaternion<$T>) -> Quaternion<$T> {
+                    Quaternion {
+                        x: q.v.x,
+                        y: q.v.y,
+                        z: q.v.z,
+                        w: q.s,
+                    }
+                }
+            }
+        };
+    }
+
+    impl_cgmath!(f32);
+    impl_cgmath!(f64);
+}
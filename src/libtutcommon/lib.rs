@@ -16,6 +16,20 @@ extern crate libc;
 
 extern crate gl;
 
+extern crate bevy_mikktspace;
+
+#[cfg(feature = "mint")]
+extern crate mint;
+
+#[cfg(feature = "glam")]
+extern crate glam;
+
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
+
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
+
 pub mod glutils;
 
 pub mod sdl;
@@ -24,4 +38,16 @@ pub mod controls;
 
 pub mod matrix;
 
+pub mod mesh;
+
 pub mod objloader;
+
+pub mod geometry;
+
+pub mod curves;
+
+pub mod color;
+
+pub mod noise;
+
+mod interop;
@@ -1,196 +1,1485 @@
 #![doc = "Linear algebra"]
+//! Types are generic over `Scalar` component type, `f32` aliases like
+//! `Vector3f` and `Matrix4f` are used to send data into OpenGL, `f64` aliases
+//! like `Vector3d` and `Matrix4d` are for precise computations on CPU.
 
-use std::ops::{Add, Mul, Sub};
+use std::{
+    error::Error,
+    fmt,
+    ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use controls::Controls;
+
+#[doc = "Floating point type of vector and matrix components."]
+pub trait Scalar:
+    Copy
+    + Default
+    + fmt::Debug
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + ApproxEq<Epsilon = Self>
+{
+    #[doc = "Zero."]
+    const ZERO: Self;
+
+    #[doc = "One."]
+    const ONE: Self;
+
+    #[doc = "Two."]
+    const TWO: Self;
+
+    #[doc = "Machine epsilon."]
+    const EPSILON: Self;
+
+    #[doc = "Convert from `f64`, rounds to nearest for `f32`."]
+    fn from_f64(v: f64) -> Self;
+
+    #[doc = "Convert into `f64` without loss."]
+    fn to_f64(self) -> f64;
+
+    #[doc = "Convert into another scalar type, may lose precision."]
+    fn cast<U: Scalar>(self) -> U {
+        U::from_f64(self.to_f64())
+    }
+
+    #[doc = "Square root."]
+    fn sqrt(self) -> Self;
+
+    #[doc = "Sine of angle in radians."]
+    fn sin(self) -> Self;
+
+    #[doc = "Cosine of angle in radians."]
+    fn cos(self) -> Self;
+
+    #[doc = "Tangent of angle in radians."]
+    fn tan(self) -> Self;
+
+    #[doc = "Arccosine in radians."]
+    fn acos(self) -> Self;
+
+    #[doc = "Absolute value."]
+    fn abs(self) -> Self;
+
+    #[doc = "Minimum of two numbers."]
+    fn min(self, other: Self) -> Self;
+
+    #[doc = "Maximum of two numbers."]
+    fn max(self, other: Self) -> Self;
+
+    #[doc = "Restrict number to interval."]
+    fn clamp(self, min: Self, max: Self) -> Self;
+
+    #[doc = "Convert degrees to radians."]
+    fn to_radians(self) -> Self;
+
+    #[doc = "Convert radians to degrees."]
+    fn to_degrees(self) -> Self;
+
+    #[doc = "Check if number is neither zero, infinite, subnormal nor NaN."]
+    fn is_normal(self) -> bool;
+
+    // Multiply column major matrix by column.
+    #[doc(hidden)]
+    fn mul_column(m: &[[Self; 4]; 4], c: &[Self; 4]) -> [Self; 4] {
+        let mut res = [Self::ZERO; 4];
+
+        for i in 0..4 {
+            // row
+            for k in 0..4 {
+                res[i] += m[k][i] * c[k];
+            }
+        }
+
+        res
+    }
+}
+
+// Methods of `Scalar` forwarded to inherent methods of float type.
+macro_rules! impl_scalar_math {
+    ($T:ident) => {
+        const ZERO: $T = 0.0;
+        const ONE: $T = 1.0;
+        const TWO: $T = 2.0;
+        const EPSILON: $T = $T::EPSILON;
+
+        fn sqrt(self) -> $T {
+            $T::sqrt(self)
+        }
+
+        fn sin(self) -> $T {
+            $T::sin(self)
+        }
+
+        fn cos(self) -> $T {
+            $T::cos(self)
+        }
+
+        fn tan(self) -> $T {
+            $T::tan(self)
+        }
+
+        fn acos(self) -> $T {
+            $T::acos(self)
+        }
+
+        fn abs(self) -> $T {
+            $T::abs(self)
+        }
+
+        fn min(self, other: $T) -> $T {
+            $T::min(self, other)
+        }
+
+        fn max(self, other: $T) -> $T {
+            $T::max(self, other)
+        }
+
+        fn clamp(self, min: $T, max: $T) -> $T {
+            $T::clamp(self, min, max)
+        }
+
+        fn to_radians(self) -> $T {
+            $T::to_radians(self)
+        }
+
+        fn to_degrees(self) -> $T {
+            $T::to_degrees(self)
+        }
+
+        fn is_normal(self) -> bool {
+            $T::is_normal(self)
+        }
+    };
+}
+
+impl Scalar for f32 {
+    impl_scalar_math!(f32);
+
+    fn from_f64(v: f64) -> f32 {
+        v as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    // Sums products from zero in the same order as the default
+    // implementation, so results are the same, including sign of zero.
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    fn mul_column(m: &[[f32; 4]; 4], c: &[f32; 4]) -> [f32; 4] {
+        use std::arch::x86_64::{
+            _mm_add_ps, _mm_loadu_ps, _mm_mul_ps, _mm_set1_ps, _mm_setzero_ps, _mm_storeu_ps,
+        };
+
+        let mut res = [0.0; 4];
+
+        // SSE is always available on x86_64.
+        unsafe {
+            let mut sum = _mm_setzero_ps();
+            for (column, &k) in m.iter().zip(c) {
+                sum = _mm_add_ps(sum, _mm_mul_ps(_mm_loadu_ps(column.as_ptr()), _mm_set1_ps(k)));
+            }
+            _mm_storeu_ps(res.as_mut_ptr(), sum);
+        }
+
+        res
+    }
+}
+
+impl Scalar for f64 {
+    impl_scalar_math!(f64);
+
+    fn from_f64(v: f64) -> f64 {
+        v
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+#[doc = "Approximate equality of floating point values."]
+/// Vectors and matrices are compared component-wise.
+pub trait ApproxEq {
+    #[doc = "Type of absolute tolerance."]
+    type Epsilon;
+
+    #[doc = "Check if absolute difference is at most `epsilon`."]
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool;
+
+    #[doc = "Check if there are at most `max_ulps` representable numbers between values."]
+    /// Zeros of different sign are equal, NaN isn't equal to anything.
+    fn ulps_eq(&self, other: &Self, max_ulps: u32) -> bool;
+}
+
+impl ApproxEq for f32 {
+    type Epsilon = f32;
+
+    fn abs_diff_eq(&self, other: &f32, epsilon: f32) -> bool {
+        (self - other).abs() <= epsilon
+    }
+
+    fn ulps_eq(&self, other: &f32, max_ulps: u32) -> bool {
+        if self == other {
+            return true;
+        }
+        if self.is_nan() || other.is_nan() || self.is_sign_negative() != other.is_sign_negative() {
+            return false;
+        }
+
+        // Bits of floats with the same sign are ordered like the floats.
+        self.to_bits().abs_diff(other.to_bits()) <= max_ulps
+    }
+}
+
+impl ApproxEq for f64 {
+    type Epsilon = f64;
+
+    fn abs_diff_eq(&self, other: &f64, epsilon: f64) -> bool {
+        (self - other).abs() <= epsilon
+    }
+
+    fn ulps_eq(&self, other: &f64, max_ulps: u32) -> bool {
+        if self == other {
+            return true;
+        }
+        if self.is_nan() || other.is_nan() || self.is_sign_negative() != other.is_sign_negative() {
+            return false;
+        }
+
+        // Bits of floats with the same sign are ordered like the floats.
+        self.to_bits().abs_diff(other.to_bits()) <= u64::from(max_ulps)
+    }
+}
+
+// Component-wise operator between two vectors for all combinations of owned
+// and borrowed operands.
+macro_rules! impl_vector_op {
+    ($V:ident, $Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $($f:tt),+) => {
+        impl<T: Scalar> $Op<&$V<T>> for &$V<T> {
+            type Output = $V<T>;
+
+            fn $op(self, _rhs: &$V<T>) -> $V<T> {
+                $V($(self.$f.$op(_rhs.$f)),+)
+            }
+        }
+
+        impl<T: Scalar> $Op<&$V<T>> for $V<T> {
+            type Output = $V<T>;
+
+            fn $op(self, _rhs: &$V<T>) -> $V<T> {
+                (&self).$op(_rhs)
+            }
+        }
+
+        impl<T: Scalar> $Op<$V<T>> for &$V<T> {
+            type Output = $V<T>;
+
+            fn $op(self, _rhs: $V<T>) -> $V<T> {
+                self.$op(&_rhs)
+            }
+        }
+
+        impl<T: Scalar> $Op<$V<T>> for $V<T> {
+            type Output = $V<T>;
+
+            fn $op(self, _rhs: $V<T>) -> $V<T> {
+                (&self).$op(&_rhs)
+            }
+        }
+
+        impl<T: Scalar> $OpAssign<&$V<T>> for $V<T> {
+            fn $op_assign(&mut self, _rhs: &$V<T>) {
+                $(self.$f.$op_assign(_rhs.$f);)+
+            }
+        }
+
+        impl<T: Scalar> $OpAssign<$V<T>> for $V<T> {
+            fn $op_assign(&mut self, _rhs: $V<T>) {
+                self.$op_assign(&_rhs)
+            }
+        }
+    };
+}
+
+// Operator between vector and scalar.
+macro_rules! impl_scalar_op {
+    ($V:ident, $Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $($f:tt),+) => {
+        impl<T: Scalar> $Op<T> for &$V<T> {
+            type Output = $V<T>;
+
+            fn $op(self, _rhs: T) -> $V<T> {
+                $V($(self.$f.$op(_rhs)),+)
+            }
+        }
+
+        impl<T: Scalar> $Op<T> for $V<T> {
+            type Output = $V<T>;
+
+            fn $op(self, _rhs: T) -> $V<T> {
+                (&self).$op(_rhs)
+            }
+        }
+
+        impl<T: Scalar> $OpAssign<T> for $V<T> {
+            fn $op_assign(&mut self, _rhs: T) {
+                $(self.$f.$op_assign(_rhs);)+
+            }
+        }
+    };
+}
+
+// Scalar multiplied by vector, it can't be generic over scalar type.
+macro_rules! impl_scalar_lhs_mul {
+    ($V:ident, $T:ident) => {
+        impl Mul<&$V<$T>> for $T {
+            type Output = $V<$T>;
+
+            fn mul(self, _rhs: &$V<$T>) -> $V<$T> {
+                _rhs * self
+            }
+        }
+
+        impl Mul<$V<$T>> for $T {
+            type Output = $V<$T>;
+
+            fn mul(self, _rhs: $V<$T>) -> $V<$T> {
+                _rhs * self
+            }
+        }
+    };
+}
+
+// Common methods and operators for vectors.
+macro_rules! impl_vector {
+    ($V:ident, $n:expr, $($f:tt),+) => {
+        impl<T: Scalar> $V<T> {
+            #[doc = "Dot product."]
+            pub fn dot(&self, _rhs: &$V<T>) -> T {
+                T::ZERO $(+ self.$f * _rhs.$f)+
+            }
+
+            #[doc = "Squared length of vector."]
+            pub fn length_squared(&self) -> T {
+                self.dot(self)
+            }
+
+            #[doc = "Length of vector."]
+            pub fn length(&self) -> T {
+                self.length_squared().sqrt()
+            }
+
+            #[doc = "Distance between points."]
+            pub fn distance(&self, other: &$V<T>) -> T {
+                (self - other).length()
+            }
+
+            #[doc = "Normalize vector to length 1."]
+            pub fn normalize(&self) -> $V<T> {
+                self / self.length()
+            }
+
+            #[doc = "Linear interpolation between vectors."]
+            pub fn lerp(&self, other: &$V<T>, t: T) -> $V<T> {
+                self + &((other - self) * t)
+            }
+
+            #[doc = "Reflect vector from surface with normal of length 1."]
+            pub fn reflect(&self, normal: &$V<T>) -> $V<T> {
+                self - &(normal * (T::TWO * self.dot(normal)))
+            }
+
+            #[doc = "Component-wise minimum."]
+            pub fn min(&self, other: &$V<T>) -> $V<T> {
+                $V($(self.$f.min(other.$f)),+)
+            }
+
+            #[doc = "Component-wise maximum."]
+            pub fn max(&self, other: &$V<T>) -> $V<T> {
+                $V($(self.$f.max(other.$f)),+)
+            }
+
+            #[doc = "Component-wise absolute value."]
+            pub fn abs(&self) -> $V<T> {
+                $V($(self.$f.abs()),+)
+            }
+
+            #[doc = "Convert components into another scalar type, may lose precision."]
+            pub fn cast<U: Scalar>(&self) -> $V<U> {
+                $V($(self.$f.cast()),+)
+            }
+        }
+
+        impl_vector_op!($V, Add, add, AddAssign, add_assign, $($f),+);
+        impl_vector_op!($V, Sub, sub, SubAssign, sub_assign, $($f),+);
+        impl_vector_op!($V, Mul, mul, MulAssign, mul_assign, $($f),+);
+        impl_vector_op!($V, Div, div, DivAssign, div_assign, $($f),+);
+
+        impl_scalar_op!($V, Mul, mul, MulAssign, mul_assign, $($f),+);
+        impl_scalar_op!($V, Div, div, DivAssign, div_assign, $($f),+);
+
+        impl_scalar_lhs_mul!($V, f32);
+        impl_scalar_lhs_mul!($V, f64);
+
+        impl<T: Scalar> Neg for &$V<T> {
+            type Output = $V<T>;
+
+            fn neg(self) -> $V<T> {
+                $V($(-self.$f),+)
+            }
+        }
+
+        impl<T: Scalar> Neg for $V<T> {
+            type Output = $V<T>;
+
+            fn neg(self) -> $V<T> {
+                -&self
+            }
+        }
+
+        impl<T> Index<usize> for $V<T> {
+            type Output = T;
+
+            fn index(&self, index: usize) -> &T {
+                match index {
+                    $($f => &self.$f,)+
+                    _ => panic!("Index {} is out of range for {}", index, stringify!($V)),
+                }
+            }
+        }
+
+        impl<T> IndexMut<usize> for $V<T> {
+            fn index_mut(&mut self, index: usize) -> &mut T {
+                match index {
+                    $($f => &mut self.$f,)+
+                    _ => panic!("Index {} is out of range for {}", index, stringify!($V)),
+                }
+            }
+        }
+
+        impl<T: Copy> From<[T; $n]> for $V<T> {
+            fn from(a: [T; $n]) -> $V<T> {
+                $V($(a[$f]),+)
+            }
+        }
+
+        impl<T> From<$V<T>> for [T; $n] {
+            fn from(v: $V<T>) -> [T; $n] {
+                [$(v.$f),+]
+            }
+        }
+
+        impl From<$V<f32>> for $V<f64> {
+            fn from(v: $V<f32>) -> $V<f64> {
+                v.cast()
+            }
+        }
+
+        impl<T: Scalar> ApproxEq for $V<T> {
+            type Epsilon = T;
+
+            fn abs_diff_eq(&self, other: &$V<T>, epsilon: T) -> bool {
+                $(self.$f.abs_diff_eq(&other.$f, epsilon))&&+
+            }
+
+            fn ulps_eq(&self, other: &$V<T>, max_ulps: u32) -> bool {
+                $(self.$f.ulps_eq(&other.$f, max_ulps))&&+
+            }
+        }
+    };
+}
+
+#[doc = "Vector with 2 components (x,y)"]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector2<T>(pub T, pub T);
+
+impl_vector!(Vector2, 2, 0, 1);
+
+#[doc = "Vector with 2 `f32` components."]
+pub type Vector2f = Vector2<f32>;
+
+#[doc = "Vector with 2 `f64` components."]
+pub type Vector2d = Vector2<f64>;
+
+#[doc = "Create `Vector2f`, keeps constructor syntax of tuple struct."]
+#[allow(non_snake_case)]
+pub fn Vector2f(x: f32, y: f32) -> Vector2f {
+    Vector2(x, y)
+}
 
 #[doc = "Vector with 3 components (x,y,z)"]
 #[repr(C)]
-#[derive(Clone, Debug)]
-pub struct Vector3f(pub f32, pub f32, pub f32);
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector3<T>(pub T, pub T, pub T);
 
-impl Vector3f {
-    #[doc = "Normalize vector to length 1."]
-    pub fn normalize(&self) -> Vector3f {
-        let l = (self.0 * self.0 + self.1 * self.1 + self.2 * self.2).sqrt();
+impl_vector!(Vector3, 3, 0, 1, 2);
 
-        Vector3f(self.0 / l, self.1 / l, self.2 / l)
-    }
+#[doc = "Vector with 3 `f32` components."]
+pub type Vector3f = Vector3<f32>;
 
+#[doc = "Vector with 3 `f64` components."]
+pub type Vector3d = Vector3<f64>;
+
+#[doc = "Create `Vector3f`, keeps constructor syntax of tuple struct."]
+#[allow(non_snake_case)]
+pub fn Vector3f(x: f32, y: f32, z: f32) -> Vector3f {
+    Vector3(x, y, z)
+}
+
+impl<T: Scalar> Vector3<T> {
     #[doc = "Cross product."]
-    pub fn cross(&self, _rhs: &Vector3f) -> Vector3f {
-        Vector3f(
+    pub fn cross(&self, _rhs: &Vector3<T>) -> Vector3<T> {
+        Vector3(
             self.1 * _rhs.2 - self.2 * _rhs.1,
             self.2 * _rhs.0 - self.0 * _rhs.2,
             self.0 * _rhs.1 - self.1 * _rhs.0,
         )
     }
+
+    #[doc = "Add 4th component."]
+    pub fn extend(&self, w: T) -> Vector4<T> {
+        Vector4(self.0, self.1, self.2, w)
+    }
+}
+
+#[doc = "Vector with 4 components (x,y,z,w)"]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector4<T>(pub T, pub T, pub T, pub T);
+
+impl_vector!(Vector4, 4, 0, 1, 2, 3);
+
+#[doc = "Vector with 4 `f32` components."]
+pub type Vector4f = Vector4<f32>;
+
+#[doc = "Vector with 4 `f64` components."]
+pub type Vector4d = Vector4<f64>;
+
+#[doc = "Create `Vector4f`, keeps constructor syntax of tuple struct."]
+#[allow(non_snake_case)]
+pub fn Vector4f(x: f32, y: f32, z: f32, w: f32) -> Vector4f {
+    Vector4(x, y, z, w)
+}
+
+impl<T: Scalar> Vector4<T> {
+    #[doc = "Drop 4th component."]
+    pub fn truncate(&self) -> Vector3<T> {
+        Vector3(self.0, self.1, self.2)
+    }
+}
+
+#[doc = "Angle in degrees."]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Deg<T = f32>(pub T);
+
+#[doc = "Angle in radians."]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Rad<T = f32>(pub T);
+
+impl<T: Scalar> From<Deg<T>> for Rad<T> {
+    fn from(a: Deg<T>) -> Rad<T> {
+        Rad(a.0.to_radians())
+    }
+}
+
+impl<T: Scalar> From<Rad<T>> for Deg<T> {
+    fn from(a: Rad<T>) -> Deg<T> {
+        Deg(a.0.to_degrees())
+    }
+}
+
+#[doc = "Matrix 4x4 to send into OpenGL."]
+#[derive(Clone, Debug, PartialEq)]
+#[repr(C)]
+pub struct Matrix4<T> {
+    data: [[T; 4]; 4], /* column major order data[j][i] points to j-th
+                        * column i-th row. */
+}
+
+#[doc = "Matrix 4x4 with `f32` components."]
+pub type Matrix4f = Matrix4<f32>;
+
+#[doc = "Matrix 4x4 with `f64` components."]
+pub type Matrix4d = Matrix4<f64>;
+
+impl<T: Scalar> Matrix4<T> {
+    #[doc = "Get raw data for OpenGL."]
+    pub fn as_ptr(&self) -> *const T {
+        self.data[0].as_ptr()
+    }
+
+    #[doc = "Create matrix from columns."]
+    pub fn from_columns(data: [[T; 4]; 4]) -> Matrix4<T> {
+        Matrix4 { data }
+    }
+
+    #[doc = "Get columns of matrix."]
+    pub fn columns(&self) -> &[[T; 4]; 4] {
+        &self.data
+    }
+
+    #[doc = "Get i-th row of matrix."]
+    pub fn row(&self, i: usize) -> Vector4<T> {
+        Vector4(
+            self.data[0][i],
+            self.data[1][i],
+            self.data[2][i],
+            self.data[3][i],
+        )
+    }
+
+    #[doc = "Convert components into another scalar type, may lose precision."]
+    pub fn cast<U: Scalar>(&self) -> Matrix4<U> {
+        let mut res = Matrix4 {
+            data: [[U::ZERO; 4]; 4],
+        };
+
+        for j in 0..4 {
+            for i in 0..4 {
+                res.data[j][i] = self.data[j][i].cast();
+            }
+        }
+
+        res
+    }
+
+    #[doc = "Get perspective projection matrix."]
+    /// `fov` is vertical field of view in degrees.
+    pub fn perspective(fov: T, aspect: T, znear: T, zfar: T) -> Matrix4<T> {
+        Matrix4::perspective_fov(Deg(fov), aspect, znear, zfar)
+    }
+
+    #[doc = "Get perspective projection matrix with field of view in degrees or radians."]
+    pub fn perspective_fov<A: Into<Rad<T>>>(fov: A, aspect: T, znear: T, zfar: T) -> Matrix4<T> {
+        let mut res = Matrix4::perspective_infinite(fov, aspect, znear);
+
+        res.data[2][2] = (zfar + znear) / (znear - zfar);
+        res.data[3][2] = T::TWO * zfar * znear / (znear - zfar);
+
+        res
+    }
+
+    #[doc = "Get perspective projection matrix with far plane at infinity."]
+    pub fn perspective_infinite<A: Into<Rad<T>>>(fov: A, aspect: T, znear: T) -> Matrix4<T> {
+        let mut res: Matrix4<T> = Default::default();
+
+        let f = T::ONE / (fov.into().0 / T::TWO).tan();
+
+        res.data[0][0] = f / aspect;
+        res.data[1][1] = f;
+        res.data[2][2] = -T::ONE;
+        res.data[2][3] = -T::ONE;
+        res.data[3][2] = -T::TWO * znear;
+        res.data[3][3] = T::ZERO;
+
+        res
+    }
+
+    #[doc = "Get perspective projection matrix with reversed depth."]
+    /// Maps near plane to depth 1 and far plane to depth 0. Use it with
+    /// `glClipControl(GL_LOWER_LEFT, GL_ZERO_TO_ONE)` and `GL_GREATER` depth
+    /// test for better depth precision.
+    pub fn perspective_reversed_z<A: Into<Rad<T>>>(
+        fov: A,
+        aspect: T,
+        znear: T,
+        zfar: T,
+    ) -> Matrix4<T> {
+        let mut res = Matrix4::perspective_infinite_reversed_z(fov, aspect, znear);
+
+        res.data[2][2] = znear / (zfar - znear);
+        res.data[3][2] = zfar * znear / (zfar - znear);
+
+        res
+    }
+
+    #[doc = "Get perspective projection matrix with reversed depth and far plane at infinity."]
+    /// Maps near plane to depth 1 and infinity to depth 0, see
+    /// `perspective_reversed_z`.
+    pub fn perspective_infinite_reversed_z<A: Into<Rad<T>>>(
+        fov: A,
+        aspect: T,
+        znear: T,
+    ) -> Matrix4<T> {
+        let mut res = Matrix4::perspective_infinite(fov, aspect, znear);
+
+        res.data[2][2] = T::ZERO;
+        res.data[3][2] = znear;
+
+        res
+    }
+
+    #[doc = "Get perspective projection matrix like `glFrustum`."]
+    /// Near plane can be off-center.
+    pub fn frustum(left: T, right: T, bottom: T, top: T, znear: T, zfar: T) -> Matrix4<T> {
+        let mut res: Matrix4<T> = Default::default();
+
+        res.data[0][0] = T::TWO * znear / (right - left);
+        res.data[1][1] = T::TWO * znear / (top - bottom);
+        res.data[2][0] = (right + left) / (right - left);
+        res.data[2][1] = (top + bottom) / (top - bottom);
+        res.data[2][2] = (zfar + znear) / (znear - zfar);
+        res.data[2][3] = -T::ONE;
+        res.data[3][2] = T::TWO * zfar * znear / (znear - zfar);
+        res.data[3][3] = T::ZERO;
+
+        res
+    }
+
+    #[doc = "Get orthographic projection matrix."]
+    pub fn ortho(left: T, right: T, bottom: T, top: T, znear: T, zfar: T) -> Matrix4<T> {
+        let mut res: Matrix4<T> = Default::default();
+
+        res.data[0][0] = T::TWO / (right - left);
+        res.data[1][1] = T::TWO / (top - bottom);
+        res.data[2][2] = -T::TWO / (zfar - znear);
+        res.data[3][0] = -(right + left) / (right - left);
+        res.data[3][1] = -(top + bottom) / (top - bottom);
+        res.data[3][2] = -(zfar + znear) / (zfar - znear);
+
+        res
+    }
+
+    #[doc = "Generate matrix for camera."]
+    pub fn look_at(eye: &Vector3<T>, center: &Vector3<T>, up: &Vector3<T>) -> Matrix4<T> {
+        let f = (center - eye).normalize();
+        let up1 = up.normalize();
+        let s = f.cross(&up1).normalize();
+        let u = s.cross(&f);
+
+        let mut res: Matrix4<T> = Default::default();
+        res.data[0][0] = s.0;
+        res.data[1][0] = s.1;
+        res.data[2][0] = s.2;
+
+        res.data[0][1] = u.0;
+        res.data[1][1] = u.1;
+        res.data[2][1] = u.2;
+
+        res.data[0][2] = -f.0;
+        res.data[1][2] = -f.1;
+        res.data[2][2] = -f.2;
+
+        let mut t: Matrix4<T> = Default::default();
+
+        t.data[3][0] = -eye.0;
+        t.data[3][1] = -eye.1;
+        t.data[3][2] = -eye.2;
+
+        res.mul(&t)
+    }
+
+    #[doc = "Generate translation matrix."]
+    pub fn translate(t: &Vector3<T>) -> Matrix4<T> {
+        let mut res: Matrix4<T> = Default::default();
+
+        res.data[3][0] = t.0;
+        res.data[3][1] = t.1;
+        res.data[3][2] = t.2;
+
+        res
+    }
+
+    #[doc = "Generate translation matrix."]
+    pub fn scale(s: &Vector3<T>) -> Matrix4<T> {
+        let mut res: Matrix4<T> = Default::default();
+
+        res.data[0][0] = s.0;
+        res.data[1][1] = s.1;
+        res.data[2][2] = s.2;
+
+        res
+    }
+
+    #[doc = "Generate rotate matrix."]
+    pub fn rotate(angle: T, axis: &Vector3<T>) -> Matrix4<T> {
+        Quaternion::from_axis_angle(angle, axis).to_matrix()
+    }
+
+    #[doc = "Matrix multiplication."]
+    /// Uses SSE for `f32` with `simd` feature on x86_64.
+    pub fn mul(&self, _rhs: &Matrix4<T>) -> Matrix4<T> {
+        Matrix4 {
+            data: [
+                T::mul_column(&self.data, &_rhs.data[0]),
+                T::mul_column(&self.data, &_rhs.data[1]),
+                T::mul_column(&self.data, &_rhs.data[2]),
+                T::mul_column(&self.data, &_rhs.data[3]),
+            ],
+        }
+    }
+
+    #[doc = "Transform point, w is 1 and result is divided by w."]
+    pub fn transform_point(&self, p: &Vector3<T>) -> Vector3<T> {
+        let v = self * p.extend(T::ONE);
+
+        v.truncate() / v.3
+    }
+
+    #[doc = "Transform direction, w is 0 so translation is ignored."]
+    pub fn transform_direction(&self, d: &Vector3<T>) -> Vector3<T> {
+        (self * d.extend(T::ZERO)).truncate()
+    }
+
+    #[doc = "Split affine matrix into translation, rotation and scale."]
+    /// Result applied as `translate * rotate * scale` gives the same matrix
+    /// if it has no shear. Negative determinant is put into X scale. Returns
+    /// `None` if any scale is zero.
+    pub fn decompose(&self) -> Option<(Vector3<T>, Quaternion<T>, Vector3<T>)> {
+        let translation = Vector3(self.data[3][0], self.data[3][1], self.data[3][2]);
+
+        let x = Vector3(self.data[0][0], self.data[0][1], self.data[0][2]);
+        let y = Vector3(self.data[1][0], self.data[1][1], self.data[1][2]);
+        let z = Vector3(self.data[2][0], self.data[2][1], self.data[2][2]);
+
+        let mut scale = Vector3(x.length(), y.length(), z.length());
+        if !(scale.0.is_normal() && scale.1.is_normal() && scale.2.is_normal()) {
+            return None;
+        }
+        if x.cross(&y).dot(&z) < T::ZERO {
+            scale.0 = -scale.0;
+        }
+
+        let (x, y, z) = (x / scale.0, y / scale.1, z / scale.2);
+        let (o, i) = (T::ZERO, T::ONE);
+        let rotation = Matrix4::from_columns([
+            [x.0, x.1, x.2, o],
+            [y.0, y.1, y.2, o],
+            [z.0, z.1, z.2, o],
+            [o, o, o, i],
+        ]);
+
+        Some((translation, Quaternion::from_matrix(&rotation), scale))
+    }
+
+    #[doc = "Transposed matrix."]
+    pub fn transpose(&self) -> Matrix4<T> {
+        let mut res: Matrix4<T> = Default::default();
+
+        for i in 0..4 {
+            for j in 0..4 {
+                res.data[j][i] = self.data[i][j];
+            }
+        }
+
+        res
+    }
+
+    #[doc = "Determinant of matrix."]
+    pub fn determinant(&self) -> T {
+        let (s, c) = self.subfactors();
+
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    #[doc = "Inverse matrix."]
+    /// Returns `None` for singular matrix.
+    pub fn inverse(&self) -> Option<Matrix4<T>> {
+        let (s, c) = self.subfactors();
+
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+        if !det.is_normal() {
+            return None;
+        }
+        let inv_det = T::ONE / det;
+
+        // Inverse commutes with transpose, so the formula doesn't depend on
+        // storage order.
+        let a = &self.data;
+        let mut res: Matrix4<T> = Default::default();
+
+        res.data[0][0] = (a[1][1] * c[5] - a[1][2] * c[4] + a[1][3] * c[3]) * inv_det;
+        res.data[0][1] = (-a[0][1] * c[5] + a[0][2] * c[4] - a[0][3] * c[3]) * inv_det;
+        res.data[0][2] = (a[3][1] * s[5] - a[3][2] * s[4] + a[3][3] * s[3]) * inv_det;
+        res.data[0][3] = (-a[2][1] * s[5] + a[2][2] * s[4] - a[2][3] * s[3]) * inv_det;
+
+        res.data[1][0] = (-a[1][0] * c[5] + a[1][2] * c[2] - a[1][3] * c[1]) * inv_det;
+        res.data[1][1] = (a[0][0] * c[5] - a[0][2] * c[2] + a[0][3] * c[1]) * inv_det;
+        res.data[1][2] = (-a[3][0] * s[5] + a[3][2] * s[2] - a[3][3] * s[1]) * inv_det;
+        res.data[1][3] = (a[2][0] * s[5] - a[2][2] * s[2] + a[2][3] * s[1]) * inv_det;
+
+        res.data[2][0] = (a[1][0] * c[4] - a[1][1] * c[2] + a[1][3] * c[0]) * inv_det;
+        res.data[2][1] = (-a[0][0] * c[4] + a[0][1] * c[2] - a[0][3] * c[0]) * inv_det;
+        res.data[2][2] = (a[3][0] * s[4] - a[3][1] * s[2] + a[3][3] * s[0]) * inv_det;
+        res.data[2][3] = (-a[2][0] * s[4] + a[2][1] * s[2] - a[2][3] * s[0]) * inv_det;
+
+        res.data[3][0] = (-a[1][0] * c[3] + a[1][1] * c[1] - a[1][2] * c[0]) * inv_det;
+        res.data[3][1] = (a[0][0] * c[3] - a[0][1] * c[1] + a[0][2] * c[0]) * inv_det;
+        res.data[3][2] = (-a[3][0] * s[3] + a[3][1] * s[1] - a[3][2] * s[0]) * inv_det;
+        res.data[3][3] = (a[2][0] * s[3] - a[2][1] * s[1] + a[2][2] * s[0]) * inv_det;
+
+        Some(res)
+    }
+
+    #[doc = "Upper-left 3x3 part of matrix."]
+    pub fn to_matrix3(&self) -> Matrix3<T> {
+        let mut res: Matrix3<T> = Default::default();
+
+        for i in 0..3 {
+            res.data[i].copy_from_slice(&self.data[i][0..3]);
+        }
+
+        res
+    }
+
+    #[doc = "Matrix to transform normals."]
+    /// Inverse-transpose of the upper-left 3x3 part, so normals stay
+    /// perpendicular to surfaces after non-uniform scale. Returns `None` for
+    /// singular matrix.
+    pub fn normal_matrix(&self) -> Option<Matrix3<T>> {
+        self.to_matrix3().inverse().map(|m| m.transpose())
+    }
+
+    // 2x2 minors of the first two and the last two columns.
+    fn subfactors(&self) -> ([T; 6], [T; 6]) {
+        let a = &self.data;
+
+        let s = [
+            a[0][0] * a[1][1] - a[1][0] * a[0][1],
+            a[0][0] * a[1][2] - a[1][0] * a[0][2],
+            a[0][0] * a[1][3] - a[1][0] * a[0][3],
+            a[0][1] * a[1][2] - a[1][1] * a[0][2],
+            a[0][1] * a[1][3] - a[1][1] * a[0][3],
+            a[0][2] * a[1][3] - a[1][2] * a[0][3],
+        ];
+
+        let c = [
+            a[2][0] * a[3][1] - a[3][0] * a[2][1],
+            a[2][0] * a[3][2] - a[3][0] * a[2][2],
+            a[2][0] * a[3][3] - a[3][0] * a[2][3],
+            a[2][1] * a[3][2] - a[3][1] * a[2][2],
+            a[2][1] * a[3][3] - a[3][1] * a[2][3],
+            a[2][2] * a[3][3] - a[3][2] * a[2][3],
+        ];
+
+        (s, c)
+    }
+}
+
+// Only borrowed matrix is multiplied by operator, so it doesn't shadow
+// inherent `Matrix4::mul`.
+impl<T: Scalar> Mul<Vector4<T>> for &Matrix4<T> {
+    type Output = Vector4<T>;
+
+    fn mul(self, _rhs: Vector4<T>) -> Vector4<T> {
+        Vector4::from(T::mul_column(&self.data, &_rhs.into()))
+    }
 }
 
-impl<'a, 'b> Add<&'b Vector3f> for &'a Vector3f {
-    type Output = Vector3f;
+impl<T: Scalar> Mul<&Vector4<T>> for &Matrix4<T> {
+    type Output = Vector4<T>;
 
-    fn add(self, _rhs: &'b Vector3f) -> Vector3f {
-        Vector3f(self.0 + _rhs.0, self.1 + _rhs.1, self.2 + _rhs.2)
+    fn mul(self, _rhs: &Vector4<T>) -> Vector4<T> {
+        self * *_rhs
     }
 }
 
-impl<'a, 'b> Sub<&'b Vector3f> for &'a Vector3f {
-    type Output = Vector3f;
+impl<T: Scalar> Default for Matrix4<T> {
+    fn default() -> Matrix4<T> {
+        let (o, i) = (T::ZERO, T::ONE);
 
-    fn sub(self, _rhs: &'b Vector3f) -> Vector3f {
-        Vector3f(self.0 - _rhs.0, self.1 - _rhs.1, self.2 - _rhs.2)
+        Matrix4 {
+            data: [[i, o, o, o], [o, i, o, o], [o, o, i, o], [o, o, o, i]],
+        }
+    }
+}
+
+impl From<Matrix4<f32>> for Matrix4<f64> {
+    fn from(m: Matrix4<f32>) -> Matrix4<f64> {
+        m.cast()
     }
 }
 
-impl<'a> Mul<f32> for &'a Vector3f {
-    type Output = Vector3f;
+impl<T: Scalar> ApproxEq for Matrix4<T> {
+    type Epsilon = T;
 
-    fn mul(self, _rhs: f32) -> Vector3f {
-        Vector3f(self.0 * _rhs, self.1 * _rhs, self.2 * _rhs)
+    fn abs_diff_eq(&self, other: &Matrix4<T>, epsilon: T) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(a, b)| a.abs_diff_eq(b, epsilon))
+    }
+
+    fn ulps_eq(&self, other: &Matrix4<T>, max_ulps: u32) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(a, b)| a.ulps_eq(b, max_ulps))
     }
 }
 
-#[doc = "Matrix 4x4 to send into OpenGL."]
-#[derive(Debug)]
+#[doc = "Quaternion for rotations."]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(C)]
-pub struct Matrix4f {
-    data: [[f32; 4]; 4], /* column major order data[j][i] points to j-th
-                          * column i-th row. */
+pub struct Quaternion<T = f32> {
+    #[doc = "X component of vector part."]
+    pub x: T,
+
+    #[doc = "Y component of vector part."]
+    pub y: T,
+
+    #[doc = "Z component of vector part."]
+    pub z: T,
+
+    #[doc = "Scalar part."]
+    pub w: T,
 }
 
-impl Matrix4f {
-    #[doc = "Get raw data for OpenGL."]
-    pub fn as_ptr(&self) -> *const f32 {
-        self.data[0].as_ptr()
+impl<T: Scalar> Quaternion<T> {
+    #[doc = "Rotation by angle in degrees around axis."]
+    pub fn from_axis_angle(angle: T, axis: &Vector3<T>) -> Quaternion<T> {
+        let half = (angle / T::TWO).to_radians();
+        let (s, c) = (half.sin(), half.cos());
+        let vn = axis.normalize();
+
+        Quaternion {
+            x: vn.0 * s,
+            y: vn.1 * s,
+            z: vn.2 * s,
+            w: c,
+        }
     }
 
-    #[doc = "Get perspective projection matrix."]
-    pub fn perspective(fov: f32, aspect: f32, znear: f32, zfar: f32) -> Matrix4f {
-        let mut res: Matrix4f = Default::default();
+    #[doc = "Rotation from Euler angles in degrees."]
+    /// Rotates around X axis by pitch first, then around Y axis by yaw and
+    /// around Z axis by roll last.
+    pub fn from_euler(pitch: T, yaw: T, roll: T) -> Quaternion<T> {
+        let (o, i) = (T::ZERO, T::ONE);
+        let qx = Quaternion::from_axis_angle(pitch, &Vector3(i, o, o));
+        let qy = Quaternion::from_axis_angle(yaw, &Vector3(o, i, o));
+        let qz = Quaternion::from_axis_angle(roll, &Vector3(o, o, i));
 
-        let f = 1.0 / (fov / 2.0).to_radians().tan();
+        qz.mul(&qy).mul(&qx)
+    }
 
-        res.data[0][0] = f / aspect;
-        res.data[1][1] = f;
-        res.data[2][2] = (zfar + znear) / (znear - zfar);
-        res.data[2][3] = -1.0;
-        res.data[3][2] = 2.0 * zfar * znear / (znear - zfar);
-        res.data[3][3] = 0.0;
+    #[doc = "Rotation part of matrix."]
+    /// Upper-left 3x3 part of matrix should be orthonormal.
+    pub fn from_matrix(m: &Matrix4<T>) -> Quaternion<T> {
+        // m[j][i] points to j-th column i-th row.
+        let m = m.columns();
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        let quarter = T::from_f64(0.25);
 
-        res
+        let res = if trace > T::ZERO {
+            let s = (trace + T::ONE).sqrt() * T::TWO;
+            Quaternion {
+                x: (m[1][2] - m[2][1]) / s,
+                y: (m[2][0] - m[0][2]) / s,
+                z: (m[0][1] - m[1][0]) / s,
+                w: quarter * s,
+            }
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (T::ONE + m[0][0] - m[1][1] - m[2][2]).sqrt() * T::TWO;
+            Quaternion {
+                x: quarter * s,
+                y: (m[1][0] + m[0][1]) / s,
+                z: (m[2][0] + m[0][2]) / s,
+                w: (m[1][2] - m[2][1]) / s,
+            }
+        } else if m[1][1] > m[2][2] {
+            let s = (T::ONE + m[1][1] - m[0][0] - m[2][2]).sqrt() * T::TWO;
+            Quaternion {
+                x: (m[1][0] + m[0][1]) / s,
+                y: quarter * s,
+                z: (m[2][1] + m[1][2]) / s,
+                w: (m[2][0] - m[0][2]) / s,
+            }
+        } else {
+            let s = (T::ONE + m[2][2] - m[0][0] - m[1][1]).sqrt() * T::TWO;
+            Quaternion {
+                x: (m[2][0] + m[0][2]) / s,
+                y: (m[2][1] + m[1][2]) / s,
+                z: quarter * s,
+                w: (m[0][1] - m[1][0]) / s,
+            }
+        };
+
+        res.normalize()
     }
 
-    #[doc = "Get orthographic projection matrix."]
-    pub fn ortho(left: f32, right: f32, bottom: f32, top: f32, znear: f32, zfar: f32) -> Matrix4f {
-        let mut res: Matrix4f = Default::default();
+    #[doc = "Rotation axis and angle in degrees."]
+    pub fn to_axis_angle(&self) -> (Vector3<T>, T) {
+        let q = self.normalize();
+        let s = (T::ONE - q.w * q.w).max(T::ZERO).sqrt();
+        let angle = T::TWO * q.w.clamp(-T::ONE, T::ONE).acos().to_degrees();
 
-        res.data[0][0] = 2.0 / (right - left);
-        res.data[1][1] = 2.0 / (top - bottom);
-        res.data[2][2] = -2.0 / (zfar - znear);
-        res.data[3][0] = -(right + left) / (right - left);
-        res.data[3][1] = -(top + bottom) / (top - bottom);
-        res.data[3][2] = -(zfar + znear) / (zfar - znear);
+        if s < T::EPSILON {
+            (Vector3(T::ONE, T::ZERO, T::ZERO), angle)
+        } else {
+            (Vector3(q.x / s, q.y / s, q.z / s), angle)
+        }
+    }
+
+    #[doc = "Rotation matrix."]
+    pub fn to_matrix(&self) -> Matrix4<T> {
+        let mut res: Matrix4<T> = Default::default();
+
+        let Quaternion { x, y, z, w } = *self;
+        let (i, two) = (T::ONE, T::TWO);
+
+        let x2 = x * x;
+        let y2 = y * y;
+        let z2 = z * z;
+        let xy = x * y;
+        let xz = x * z;
+        let yz = y * z;
+        let wx = w * x;
+        let wy = w * y;
+        let wz = w * z;
+
+        res.data[0][0] = i - two * (y2 + z2);
+        res.data[0][1] = two * (xy + wz);
+        res.data[0][2] = two * (xz - wy);
+
+        res.data[1][0] = two * (xy - wz);
+        res.data[1][1] = i - two * (x2 + z2);
+        res.data[1][2] = two * (yz + wx);
+
+        res.data[2][0] = two * (xz + wy);
+        res.data[2][1] = two * (yz - wx);
+        res.data[2][2] = i - two * (x2 + y2);
 
         res
     }
 
-    #[doc = "Generate matrix for camera."]
-    pub fn look_at(eye: &Vector3f, center: &Vector3f, up: &Vector3f) -> Matrix4f {
-        let f = (center - eye).normalize();
-        let up1 = up.normalize();
-        let s = f.cross(&up1).normalize();
-        let u = s.cross(&f);
+    #[doc = "Convert components into another scalar type, may lose precision."]
+    pub fn cast<U: Scalar>(&self) -> Quaternion<U> {
+        Quaternion {
+            x: self.x.cast(),
+            y: self.y.cast(),
+            z: self.z.cast(),
+            w: self.w.cast(),
+        }
+    }
 
-        let mut res: Matrix4f = Default::default();
-        res.data[0][0] = s.0;
-        res.data[1][0] = s.1;
-        res.data[2][0] = s.2;
+    #[doc = "Quaternion multiplication."]
+    /// Result rotates by `_rhs` first and by `self` after it.
+    pub fn mul(&self, _rhs: &Quaternion<T>) -> Quaternion<T> {
+        Quaternion {
+            x: self.w * _rhs.x + self.x * _rhs.w + self.y * _rhs.z - self.z * _rhs.y,
+            y: self.w * _rhs.y - self.x * _rhs.z + self.y * _rhs.w + self.z * _rhs.x,
+            z: self.w * _rhs.z + self.x * _rhs.y - self.y * _rhs.x + self.z * _rhs.w,
+            w: self.w * _rhs.w - self.x * _rhs.x - self.y * _rhs.y - self.z * _rhs.z,
+        }
+    }
 
-        res.data[0][1] = u.0;
-        res.data[1][1] = u.1;
-        res.data[2][1] = u.2;
+    #[doc = "Dot product."]
+    pub fn dot(&self, _rhs: &Quaternion<T>) -> T {
+        self.x * _rhs.x + self.y * _rhs.y + self.z * _rhs.z + self.w * _rhs.w
+    }
 
-        res.data[0][2] = -f.0;
-        res.data[1][2] = -f.1;
-        res.data[2][2] = -f.2;
+    #[doc = "Length of quaternion."]
+    pub fn length(&self) -> T {
+        self.dot(self).sqrt()
+    }
 
-        let mut t: Matrix4f = Default::default();
+    #[doc = "Normalize quaternion to length 1."]
+    pub fn normalize(&self) -> Quaternion<T> {
+        let l = self.length();
 
-        t.data[3][0] = -eye.0;
-        t.data[3][1] = -eye.1;
-        t.data[3][2] = -eye.2;
+        Quaternion {
+            x: self.x / l,
+            y: self.y / l,
+            z: self.z / l,
+            w: self.w / l,
+        }
+    }
 
-        res.mul(&t)
+    #[doc = "Conjugate quaternion."]
+    /// It's the inverse rotation for quaternion of length 1.
+    pub fn conjugate(&self) -> Quaternion<T> {
+        Quaternion {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+            w: self.w,
+        }
     }
 
-    #[doc = "Generate translation matrix."]
-    pub fn translate(t: &Vector3f) -> Matrix4f {
-        let mut res: Matrix4f = Default::default();
+    #[doc = "Inverse quaternion."]
+    /// Returns `None` for zero quaternion.
+    pub fn inverse(&self) -> Option<Quaternion<T>> {
+        let l2 = self.dot(self);
+        if !l2.is_normal() {
+            return None;
+        }
 
-        res.data[3][0] = t.0;
-        res.data[3][1] = t.1;
-        res.data[3][2] = t.2;
+        let c = self.conjugate();
+        Some(Quaternion {
+            x: c.x / l2,
+            y: c.y / l2,
+            z: c.z / l2,
+            w: c.w / l2,
+        })
+    }
 
-        res
+    #[doc = "Rotate vector."]
+    pub fn rotate_vector(&self, v: &Vector3<T>) -> Vector3<T> {
+        // v + 2w(q x v) + 2q x (q x v)
+        let q = Vector3(self.x, self.y, self.z);
+        let t = q.cross(v) * T::TWO;
+
+        v + t * self.w + q.cross(&t)
     }
 
-    #[doc = "Generate translation matrix."]
-    pub fn scale(s: &Vector3f) -> Matrix4f {
-        let mut res: Matrix4f = Default::default();
+    #[doc = "Normalized linear interpolation."]
+    /// Follows the shortest path, but angular speed isn't constant.
+    pub fn nlerp(&self, other: &Quaternion<T>, t: T) -> Quaternion<T> {
+        let k = if self.dot(other) < T::ZERO {
+            -T::ONE
+        } else {
+            T::ONE
+        };
 
-        res.data[0][0] = s.0;
-        res.data[1][1] = s.1;
-        res.data[2][2] = s.2;
+        let res = Quaternion {
+            x: self.x + (k * other.x - self.x) * t,
+            y: self.y + (k * other.y - self.y) * t,
+            z: self.z + (k * other.z - self.z) * t,
+            w: self.w + (k * other.w - self.w) * t,
+        };
 
-        res
+        res.normalize()
     }
 
-    #[doc = "Generate rotate matrix."]
-    pub fn rotate(angle: f32, axis: &Vector3f) -> Matrix4f {
-        let mut res: Matrix4f = Default::default();
+    #[doc = "Spherical linear interpolation."]
+    /// Follows the shortest path with constant angular speed.
+    pub fn slerp(&self, other: &Quaternion<T>, t: T) -> Quaternion<T> {
+        let mut cos_theta = self.dot(other);
+        let k = if cos_theta < T::ZERO {
+            cos_theta = -cos_theta;
+            -T::ONE
+        } else {
+            T::ONE
+        };
 
-        let a = (angle / 2.0).to_radians().sin();
-        let vn = axis.normalize();
+        // sin(theta) is too small for close rotations.
+        if cos_theta > T::from_f64(0.9995) {
+            return self.nlerp(other, t);
+        }
 
-        let x = vn.0 * a;
-        let y = vn.1 * a;
-        let z = vn.2 * a;
-        let w = (angle / 2.0).to_radians().cos();
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let a = ((T::ONE - t) * theta).sin() / sin_theta;
+        let b = k * (t * theta).sin() / sin_theta;
 
-        let x2 = x * x;
-        let y2 = y * y;
-        let z2 = z * z;
-        let xy = x * y;
-        let xz = x * z;
-        let yz = y * z;
-        let wx = w * x;
-        let wy = w * y;
-        let wz = w * z;
+        Quaternion {
+            x: a * self.x + b * other.x,
+            y: a * self.y + b * other.y,
+            z: a * self.z + b * other.z,
+            w: a * self.w + b * other.w,
+        }
+    }
+}
+
+impl<T: Scalar> Default for Quaternion<T> {
+    fn default() -> Quaternion<T> {
+        Quaternion {
+            x: T::ZERO,
+            y: T::ZERO,
+            z: T::ZERO,
+            w: T::ONE,
+        }
+    }
+}
+
+impl From<Quaternion<f32>> for Quaternion<f64> {
+    fn from(q: Quaternion<f32>) -> Quaternion<f64> {
+        q.cast()
+    }
+}
+
+// `q` and `-q` are the same rotation, but they aren't equal here.
+impl<T: Scalar> ApproxEq for Quaternion<T> {
+    type Epsilon = T;
+
+    fn abs_diff_eq(&self, other: &Quaternion<T>, epsilon: T) -> bool {
+        self.x.abs_diff_eq(&other.x, epsilon)
+            && self.y.abs_diff_eq(&other.y, epsilon)
+            && self.z.abs_diff_eq(&other.z, epsilon)
+            && self.w.abs_diff_eq(&other.w, epsilon)
+    }
+
+    fn ulps_eq(&self, other: &Quaternion<T>, max_ulps: u32) -> bool {
+        self.x.ulps_eq(&other.x, max_ulps)
+            && self.y.ulps_eq(&other.y, max_ulps)
+            && self.z.ulps_eq(&other.z, max_ulps)
+            && self.w.ulps_eq(&other.w, max_ulps)
+    }
+}
+
+#[doc = "Translation, rotation and scale of object."]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform<T = f32> {
+    #[doc = "Translation."]
+    pub translation: Vector3<T>,
+
+    #[doc = "Rotation."]
+    pub rotation: Quaternion<T>,
+
+    #[doc = "Scale along each axis."]
+    pub scale: Vector3<T>,
+}
+
+impl<T: Scalar> Transform<T> {
+    #[doc = "Get transform from affine matrix."]
+    /// Returns `None` if any scale is zero.
+    pub fn from_matrix(m: &Matrix4<T>) -> Option<Transform<T>> {
+        let (translation, rotation, scale) = m.decompose()?;
+
+        Some(Transform {
+            translation,
+            rotation,
+            scale,
+        })
+    }
+
+    #[doc = "Model matrix, scale first, then rotation and translation."]
+    pub fn to_matrix(&self) -> Matrix4<T> {
+        Matrix4::translate(&self.translation)
+            .mul(&self.rotation.to_matrix())
+            .mul(&Matrix4::scale(&self.scale))
+    }
+
+    #[doc = "Convert components into another scalar type, may lose precision."]
+    pub fn cast<U: Scalar>(&self) -> Transform<U> {
+        Transform {
+            translation: self.translation.cast(),
+            rotation: self.rotation.cast(),
+            scale: self.scale.cast(),
+        }
+    }
+
+    #[doc = "Transform point."]
+    pub fn transform_point(&self, p: &Vector3<T>) -> Vector3<T> {
+        self.rotation.rotate_vector(&(p * self.scale)) + self.translation
+    }
+
+    #[doc = "Transform direction, translation is ignored."]
+    pub fn transform_direction(&self, d: &Vector3<T>) -> Vector3<T> {
+        self.rotation.rotate_vector(&(d * self.scale))
+    }
+
+    #[doc = "Combine transforms, `child` is applied first."]
+    /// Exact if `self` has uniform scale, otherwise shear is lost.
+    pub fn mul(&self, child: &Transform<T>) -> Transform<T> {
+        Transform {
+            translation: self.transform_point(&child.translation),
+            rotation: self.rotation.mul(&child.rotation),
+            scale: self.scale * child.scale,
+        }
+    }
+
+    #[doc = "Inverse transform."]
+    /// Exact if scale is uniform. Returns `None` if any scale is zero.
+    pub fn inverse(&self) -> Option<Transform<T>> {
+        if !(self.scale.0.is_normal() && self.scale.1.is_normal() && self.scale.2.is_normal()) {
+            return None;
+        }
+
+        let rotation = self.rotation.conjugate();
+        let scale = Vector3(T::ONE, T::ONE, T::ONE) / self.scale;
+
+        Some(Transform {
+            translation: rotation.rotate_vector(&-self.translation) * scale,
+            rotation,
+            scale,
+        })
+    }
+
+    #[doc = "Interpolate transforms, rotation is interpolated with slerp."]
+    pub fn lerp(&self, other: &Transform<T>, t: T) -> Transform<T> {
+        Transform {
+            translation: self.translation.lerp(&other.translation, t),
+            rotation: self.rotation.slerp(&other.rotation, t),
+            scale: self.scale.lerp(&other.scale, t),
+        }
+    }
+}
+
+impl<T: Scalar> Default for Transform<T> {
+    fn default() -> Transform<T> {
+        Transform {
+            translation: Vector3(T::ZERO, T::ZERO, T::ZERO),
+            rotation: Default::default(),
+            scale: Vector3(T::ONE, T::ONE, T::ONE),
+        }
+    }
+}
+
+impl From<Transform<f32>> for Transform<f64> {
+    fn from(t: Transform<f32>) -> Transform<f64> {
+        t.cast()
+    }
+}
+
+#[doc = "Matrix 3x3 to send into OpenGL."]
+#[derive(Clone, Debug, PartialEq)]
+#[repr(C)]
+pub struct Matrix3<T> {
+    data: [[T; 3]; 3], /* column major order data[j][i] points to j-th
+                        * column i-th row. */
+}
+
+#[doc = "Matrix 3x3 with `f32` components."]
+pub type Matrix3f = Matrix3<f32>;
+
+#[doc = "Matrix 3x3 with `f64` components."]
+pub type Matrix3d = Matrix3<f64>;
+
+impl<T: Scalar> Matrix3<T> {
+    #[doc = "Get raw data for OpenGL."]
+    pub fn as_ptr(&self) -> *const T {
+        self.data[0].as_ptr()
+    }
+
+    #[doc = "Create matrix from columns."]
+    pub fn from_columns(data: [[T; 3]; 3]) -> Matrix3<T> {
+        Matrix3 { data }
+    }
 
-        res.data[0][0] = 1.0 - 2.0 * (y2 + z2);
-        res.data[0][1] = 2.0 * (xy + wz);
-        res.data[0][2] = 2.0 * (xz - wy);
+    #[doc = "Get columns of matrix."]
+    pub fn columns(&self) -> &[[T; 3]; 3] {
+        &self.data
+    }
 
-        res.data[1][0] = 2.0 * (xy - wz);
-        res.data[1][1] = 1.0 - 2.0 * (x2 + z2);
-        res.data[1][2] = 2.0 * (yz + wx);
+    #[doc = "Convert components into another scalar type, may lose precision."]
+    pub fn cast<U: Scalar>(&self) -> Matrix3<U> {
+        let mut res = Matrix3 {
+            data: [[U::ZERO; 3]; 3],
+        };
 
-        res.data[2][0] = 2.0 * (xz + wy);
-        res.data[2][1] = 2.0 * (yz - wx);
-        res.data[2][2] = 1.0 - 2.0 * (x2 + y2);
+        for j in 0..3 {
+            for i in 0..3 {
+                res.data[j][i] = self.data[j][i].cast();
+            }
+        }
 
         res
     }
 
     #[doc = "Matrix multiplication."]
-    pub fn mul(&self, _rhs: &Matrix4f) -> Matrix4f {
-        let mut res: Matrix4f = Default::default();
+    pub fn mul(&self, _rhs: &Matrix3<T>) -> Matrix3<T> {
+        let mut res: Matrix3<T> = Default::default();
 
-        for i in 0..4 {
+        for i in 0..3 {
             // row
-            for j in 0..4 {
+            for j in 0..3 {
                 // column
-                let mut sum = 0.0;
-                for k in 0..4 {
+                let mut sum = T::ZERO;
+                for k in 0..3 {
                     sum += self.data[k][i] * _rhs.data[j][k];
                 }
                 res.data[j][i] = sum;
@@ -199,17 +1488,270 @@ impl Matrix4f {
 
         res
     }
+
+    #[doc = "Transposed matrix."]
+    pub fn transpose(&self) -> Matrix3<T> {
+        let mut res: Matrix3<T> = Default::default();
+
+        for i in 0..3 {
+            for j in 0..3 {
+                res.data[j][i] = self.data[i][j];
+            }
+        }
+
+        res
+    }
+
+    #[doc = "Determinant of matrix."]
+    pub fn determinant(&self) -> T {
+        let a = &self.data;
+
+        a[0][0] * (a[1][1] * a[2][2] - a[2][1] * a[1][2])
+            - a[1][0] * (a[0][1] * a[2][2] - a[2][1] * a[0][2])
+            + a[2][0] * (a[0][1] * a[1][2] - a[1][1] * a[0][2])
+    }
+
+    #[doc = "Inverse matrix."]
+    /// Returns `None` for singular matrix.
+    pub fn inverse(&self) -> Option<Matrix3<T>> {
+        let det = self.determinant();
+        if !det.is_normal() {
+            return None;
+        }
+        let inv_det = T::ONE / det;
+
+        let a = &self.data;
+        let mut res: Matrix3<T> = Default::default();
+
+        res.data[0][0] = (a[1][1] * a[2][2] - a[2][1] * a[1][2]) * inv_det;
+        res.data[0][1] = (a[2][1] * a[0][2] - a[0][1] * a[2][2]) * inv_det;
+        res.data[0][2] = (a[0][1] * a[1][2] - a[1][1] * a[0][2]) * inv_det;
+
+        res.data[1][0] = (a[2][0] * a[1][2] - a[1][0] * a[2][2]) * inv_det;
+        res.data[1][1] = (a[0][0] * a[2][2] - a[2][0] * a[0][2]) * inv_det;
+        res.data[1][2] = (a[1][0] * a[0][2] - a[0][0] * a[1][2]) * inv_det;
+
+        res.data[2][0] = (a[1][0] * a[2][1] - a[2][0] * a[1][1]) * inv_det;
+        res.data[2][1] = (a[2][0] * a[0][1] - a[0][0] * a[2][1]) * inv_det;
+        res.data[2][2] = (a[0][0] * a[1][1] - a[1][0] * a[0][1]) * inv_det;
+
+        Some(res)
+    }
 }
 
-impl Default for Matrix4f {
-    fn default() -> Matrix4f {
-        Matrix4f {
-            data: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
-            ],
+impl<T: Scalar> Mul<Vector3<T>> for &Matrix3<T> {
+    type Output = Vector3<T>;
+
+    fn mul(self, _rhs: Vector3<T>) -> Vector3<T> {
+        let mut res = Vector3::default();
+
+        for i in 0..3 {
+            // row
+            for k in 0..3 {
+                res[i] += self.data[k][i] * _rhs[k];
+            }
+        }
+
+        res
+    }
+}
+
+impl<T: Scalar> Mul<&Vector3<T>> for &Matrix3<T> {
+    type Output = Vector3<T>;
+
+    fn mul(self, _rhs: &Vector3<T>) -> Vector3<T> {
+        self * *_rhs
+    }
+}
+
+impl<T: Scalar> Default for Matrix3<T> {
+    fn default() -> Matrix3<T> {
+        let (o, i) = (T::ZERO, T::ONE);
+
+        Matrix3 {
+            data: [[i, o, o], [o, i, o], [o, o, i]],
         }
     }
 }
+
+impl From<Matrix3<f32>> for Matrix3<f64> {
+    fn from(m: Matrix3<f32>) -> Matrix3<f64> {
+        m.cast()
+    }
+}
+
+impl<T: Scalar> ApproxEq for Matrix3<T> {
+    type Epsilon = T;
+
+    fn abs_diff_eq(&self, other: &Matrix3<T>, epsilon: T) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(a, b)| a.abs_diff_eq(b, epsilon))
+    }
+
+    fn ulps_eq(&self, other: &Matrix3<T>, max_ulps: u32) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(a, b)| a.ulps_eq(b, max_ulps))
+    }
+}
+
+#[doc = "Error of popping from empty matrix stack."]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StackUnderflow;
+
+impl fmt::Display for StackUnderflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "matrix stack underflow")
+    }
+}
+
+impl Error for StackUnderflow {}
+
+#[doc = "Stack of model matrices for hierarchical drawing like `glPushMatrix`."]
+#[derive(Clone, Debug, Default)]
+pub struct MatrixStack {
+    current: Matrix4f,
+    saved: Vec<Matrix4f>,
+}
+
+impl MatrixStack {
+    #[doc = "Create stack with identity matrix."]
+    pub fn new() -> MatrixStack {
+        Default::default()
+    }
+
+    #[doc = "Current matrix."]
+    pub fn current(&self) -> &Matrix4f {
+        &self.current
+    }
+
+    #[doc = "Number of saved matrices."]
+    pub fn depth(&self) -> usize {
+        self.saved.len()
+    }
+
+    #[doc = "Save current matrix."]
+    pub fn push(&mut self) {
+        self.saved.push(self.current.clone());
+    }
+
+    #[doc = "Restore last saved matrix."]
+    /// Current matrix is unchanged on error.
+    pub fn pop(&mut self) -> Result<(), StackUnderflow> {
+        self.current = self.saved.pop().ok_or(StackUnderflow)?;
+
+        Ok(())
+    }
+
+    #[doc = "Replace current matrix."]
+    pub fn load(&mut self, m: Matrix4f) {
+        self.current = m;
+    }
+
+    #[doc = "Replace current matrix with identity."]
+    pub fn load_identity(&mut self) {
+        self.current = Default::default();
+    }
+
+    #[doc = "Multiply current matrix by `m` on the right."]
+    pub fn multiply(&mut self, m: &Matrix4f) {
+        self.current = self.current.mul(m);
+    }
+
+    #[doc = "Multiply current matrix by translation."]
+    pub fn translate(&mut self, t: &Vector3f) {
+        self.multiply(&Matrix4f::translate(t));
+    }
+
+    #[doc = "Multiply current matrix by rotation, angle is in degrees."]
+    pub fn rotate(&mut self, angle: f32, axis: &Vector3f) {
+        self.multiply(&Matrix4f::rotate(angle, axis));
+    }
+
+    #[doc = "Multiply current matrix by scale."]
+    pub fn scale(&mut self, s: &Vector3f) {
+        self.multiply(&Matrix4f::scale(s));
+    }
+
+    #[doc = "Model-view-projection matrix for current model matrix."]
+    pub fn mvp(&self, controls: &Controls) -> Matrix4f {
+        controls.projection.mul(&controls.view).mul(&self.current)
+    }
+}
+
+#[doc = "Viewport rectangle in window coordinates, same as `glViewport` arguments."]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    #[doc = "Lower left corner X."]
+    pub x: i32,
+
+    #[doc = "Lower left corner Y."]
+    pub y: i32,
+
+    #[doc = "Width in pixels."]
+    pub width: i32,
+
+    #[doc = "Height in pixels."]
+    pub height: i32,
+}
+
+#[doc = "Map object coordinates to window coordinates like `gluProject`."]
+/// Depth of result is in `0..1` range for points between near and far planes.
+/// Returns `None` for points on eye plane or behind camera, where clip `w` is
+/// not positive.
+pub fn project<T: Scalar>(
+    obj: &Vector3<T>,
+    mvp: &Matrix4<T>,
+    viewport: &Viewport,
+) -> Option<Vector3<T>> {
+    let clip = mvp * obj.extend(T::ONE);
+    if clip.3 <= T::ZERO {
+        return None;
+    }
+    let ndc = clip.truncate() / clip.3;
+    let (x, y) = (
+        T::from_f64(viewport.x.into()),
+        T::from_f64(viewport.y.into()),
+    );
+    let (w, h) = (
+        T::from_f64(viewport.width.into()),
+        T::from_f64(viewport.height.into()),
+    );
+
+    Some(Vector3(
+        x + w * (ndc.0 + T::ONE) / T::TWO,
+        y + h * (ndc.1 + T::ONE) / T::TWO,
+        (ndc.2 + T::ONE) / T::TWO,
+    ))
+}
+
+#[doc = "Map window coordinates to object coordinates like `gluUnProject`."]
+/// Returns `None` if `mvp` is singular.
+pub fn unproject<T: Scalar>(
+    win: &Vector3<T>,
+    mvp: &Matrix4<T>,
+    viewport: &Viewport,
+) -> Option<Vector3<T>> {
+    let inv = mvp.inverse()?;
+    let (x, y) = (
+        T::from_f64(viewport.x.into()),
+        T::from_f64(viewport.y.into()),
+    );
+    let (w, h) = (
+        T::from_f64(viewport.width.into()),
+        T::from_f64(viewport.height.into()),
+    );
+
+    let ndc = Vector3(
+        T::TWO * (win.0 - x) / w - T::ONE,
+        T::TWO * (win.1 - y) / h - T::ONE,
+        T::TWO * win.2 - T::ONE,
+    );
+
+    Some(inv.transform_point(&ndc))
+}
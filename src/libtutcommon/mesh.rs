@@ -0,0 +1,808 @@
+#![doc = "Triangle meshes and their OpenGL buffers."]
+
+use std::{self, collections::HashMap, ops::Range, path::PathBuf};
+
+use gl::{
+    self, types::{GLenum, GLint, GLsizei, GLuint, GLvoid},
+};
+
+use color::Rgb;
+
+use geometry::Aabb;
+
+use matrix::{Vector2f, Vector3f, Vector4f};
+
+#[doc = "Surface properties from MTL file."]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material {
+    #[doc = "Name used by `usemtl`."]
+    pub name: String,
+
+    #[doc = "Ambient color, `Ka`."]
+    pub ambient: Rgb,
+
+    #[doc = "Diffuse color, `Kd`."]
+    pub diffuse: Rgb,
+
+    #[doc = "Specular color, `Ks`."]
+    pub specular: Rgb,
+
+    #[doc = "Specular exponent, `Ns`."]
+    pub shininess: f32,
+
+    #[doc = "Opacity, `d`, 1 is opaque."]
+    pub opacity: f32,
+
+    #[doc = "Illumination model, `illum`."]
+    pub illumination: u32,
+
+    #[doc = "Diffuse texture, `map_Kd`."]
+    pub diffuse_map: Option<PathBuf>,
+
+    #[doc = "Bump or normal map, `map_Bump`."]
+    pub bump_map: Option<PathBuf>,
+
+    #[doc = "Specular texture, `map_Ks`."]
+    pub specular_map: Option<PathBuf>,
+}
+
+impl Material {
+    #[doc = "White diffuse material without textures."]
+    pub fn new(name: &str) -> Material {
+        Material {
+            name: name.to_owned(),
+            ambient: Rgb::new(0.0, 0.0, 0.0),
+            diffuse: Rgb::new(1.0, 1.0, 1.0),
+            specular: Rgb::new(0.0, 0.0, 0.0),
+            shininess: 0.0,
+            opacity: 1.0,
+            illumination: 1,
+            diffuse_map: None,
+            bump_map: None,
+            specular_map: None,
+        }
+    }
+}
+
+#[doc = "Part of mesh drawn with single call."]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Submesh {
+    #[doc = "Range of indices, or of vertices if mesh isn't indexed."]
+    pub range: Range<usize>,
+
+    #[doc = "Name of object or group."]
+    pub name: Option<String>,
+
+    #[doc = "Index of material in mesh."]
+    pub material: Option<usize>,
+}
+
+#[doc = "Weight of triangles in smooth normals."]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NormalWeighting {
+    #[doc = "Larger triangles have more weight."]
+    #[default]
+    Area,
+
+    #[doc = "Triangles with wider corner at the vertex have more weight."]
+    Angle,
+}
+
+#[doc = "Options of normal generation."]
+#[derive(Clone, Debug, PartialEq)]
+pub struct NormalOptions {
+    #[doc = "Smooth normals, or normals of triangles."]
+    pub smooth: bool,
+
+    #[doc = "Weight of triangles in smooth normals."]
+    pub weighting: NormalWeighting,
+
+    #[doc = "Largest angle in radians between smoothed triangles."]
+    /// Only used if mesh has no smoothing groups.
+    pub crease_angle: f32,
+}
+
+impl Default for NormalOptions {
+    fn default() -> NormalOptions {
+        NormalOptions {
+            smooth: true,
+            weighting: NormalWeighting::Area,
+            crease_angle: std::f32::consts::FRAC_PI_3,
+        }
+    }
+}
+
+// Vector of length 1, degenerate triangles have zero normal.
+fn unit(v: &Vector3f) -> Vector3f {
+    let length = v.length();
+    if length > 0.0 {
+        v / length
+    } else {
+        *v
+    }
+}
+
+// Angle between edges at corner `i` of triangle.
+fn corner_angle<F: Fn(usize) -> Vector3f>(position: F, i: usize) -> f32 {
+    let first = i - i % 3;
+    let p = position(i);
+    let a = unit(&(position(first + (i + 1) % 3) - p));
+    let b = unit(&(position(first + (i + 2) % 3) - p));
+    a.dot(&b).clamp(-1.0, 1.0).acos()
+}
+
+// Bits of attributes, -0.0 is the same as 0.0.
+fn bits<const N: usize>(values: [f32; N]) -> [u32; N] {
+    values.map(|x| (x + 0.0).to_bits())
+}
+
+// Triangles of mesh for MikkTSpace, tangents are written for each corner.
+struct MikkTSpace<'a> {
+    mesh: &'a Mesh,
+    corners: &'a [usize],
+    tangents: Vec<Vector4f>,
+}
+
+impl<'a> bevy_mikktspace::Geometry for MikkTSpace<'a> {
+    fn num_faces(&self) -> usize {
+        self.corners.len() / 3
+    }
+
+    fn num_vertices_of_face(&self, _face: usize) -> usize {
+        3
+    }
+
+    fn position(&self, face: usize, vert: usize) -> [f32; 3] {
+        let p = self.mesh.positions[self.corners[3 * face + vert]];
+        [p.0, p.1, p.2]
+    }
+
+    fn normal(&self, face: usize, vert: usize) -> [f32; 3] {
+        let n = self.mesh.normals[self.corners[3 * face + vert]];
+        [n.0, n.1, n.2]
+    }
+
+    fn tex_coord(&self, face: usize, vert: usize) -> [f32; 2] {
+        let uv = self.mesh.uvs[self.corners[3 * face + vert]];
+        [uv.0, uv.1]
+    }
+
+    // Corners of degenerate triangles without neighbours get default tangent
+    // like in reference implementation.
+    fn set_tangent(
+        &mut self,
+        tangent_space: Option<bevy_mikktspace::TangentSpace>,
+        face: usize,
+        vert: usize,
+    ) {
+        let [x, y, z, w] = tangent_space.unwrap_or_default().tangent_encoded();
+        let n = unit(&self.mesh.normals[self.corners[3 * face + vert]]);
+        let t = Vector3f(x, y, z);
+        let mut t = unit(&(t - n * n.dot(&t)));
+        // Triangles without UV area can't tell direction.
+        if t == Vector3f::default() {
+            let axis = if n.0.abs() < 0.9 {
+                Vector3f(1.0, 0.0, 0.0)
+            } else {
+                Vector3f(0.0, 1.0, 0.0)
+            };
+            t = unit(&n.cross(&axis));
+        }
+        self.tangents[3 * face + vert] = Vector4f(t.0, t.1, t.2, w);
+    }
+}
+
+#[doc = "Triangle list with per-vertex attributes."]
+/// `uvs`, `normals` and `tangents` are either empty or have the same length
+/// as `positions`. Without `indices` every 3 vertices form a triangle.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mesh {
+    #[doc = "Vertex positions."]
+    pub positions: Vec<Vector3f>,
+
+    #[doc = "Texture coordinates."]
+    pub uvs: Vec<Vector2f>,
+
+    #[doc = "Vertex normals."]
+    pub normals: Vec<Vector3f>,
+
+    #[doc = "Vertex tangents, `w` is handedness of bitangent."]
+    pub tangents: Vec<Vector4f>,
+
+    #[doc = "Every 3 indices form a triangle."]
+    pub indices: Option<Vec<u32>>,
+
+    #[doc = "Bounding box of positions, `None` for empty mesh."]
+    pub bounds: Option<Aabb>,
+
+    #[doc = "Parts of mesh, they cover all triangles."]
+    pub submeshes: Vec<Submesh>,
+
+    #[doc = "Materials of submeshes."]
+    pub materials: Vec<Material>,
+
+    #[doc = "Smoothing group of each triangle, 0 means flat triangle."]
+    /// Empty if mesh has no smoothing groups.
+    pub smoothing_groups: Vec<u32>,
+}
+
+impl Mesh {
+    #[doc = "Number of vertices."]
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    #[doc = "Number of indices, or of vertices if mesh isn't indexed."]
+    pub fn element_count(&self) -> usize {
+        match self.indices {
+            Some(ref indices) => indices.len(),
+            None => self.positions.len(),
+        }
+    }
+
+    #[doc = "Number of triangles."]
+    pub fn triangle_count(&self) -> usize {
+        self.element_count() / 3
+    }
+
+    #[doc = "Recalculate bounding box after positions were changed."]
+    pub fn update_bounds(&mut self) {
+        self.bounds = Aabb::from_points(&self.positions);
+    }
+
+    #[doc = "Negate V texture coordinate."]
+    /// DDS textures are stored upside down.
+    pub fn invert_v(&mut self) {
+        for uv in &mut self.uvs {
+            uv.1 = -uv.1;
+        }
+    }
+
+    // Vertex of each triangle corner.
+    fn corners(&self) -> Vec<usize> {
+        match self.indices {
+            Some(ref indices) => indices.iter().map(|&i| i as usize).collect(),
+            None => (0..self.positions.len()).collect(),
+        }
+    }
+
+    #[doc = "Replace normals with smooth ones calculated from triangles."]
+    pub fn generate_normals(&mut self) {
+        self.generate_normals_with(&NormalOptions::default());
+    }
+
+    #[doc = "Replace normals with ones calculated from triangles."]
+    /// Smooth normals are shared by triangles around the same position with
+    /// the same smoothing group, group 0 is flat. Without smoothing groups
+    /// triangles are smoothed unless they meet at more than the crease angle.
+    /// Indexed mesh gets separate vertices for each normal of shared vertex.
+    /// Existing tangents are generated again for new normals.
+    pub fn generate_normals_with(&mut self, options: &NormalOptions) {
+        let corners = self.corners();
+        let position = |i: usize| self.positions[corners[i]];
+        let key = |p: Vector3f| bits([p.0, p.1, p.2]);
+        let triangles = corners.len() / 3;
+
+        // Length of cross product is twice area of triangle.
+        let areas: Vec<Vector3f> = (0..triangles)
+            .map(|t| {
+                let a = position(3 * t);
+                (position(3 * t + 1) - a).cross(&(position(3 * t + 2) - a))
+            })
+            .collect();
+        let face_normals: Vec<Vector3f> = areas.iter().map(unit).collect();
+
+        let smoothed = |t: usize, other: usize| {
+            if t == other {
+                return true;
+            }
+            match self.smoothing_groups.get(t) {
+                Some(&group) => group != 0 && self.smoothing_groups[other] == group,
+                // Degenerate triangles take normals of neighbours.
+                None => {
+                    let cos = face_normals[t].dot(&face_normals[other]);
+                    face_normals[t] == Vector3f::default()
+                        || cos.clamp(-1.0, 1.0).acos() <= options.crease_angle
+                }
+            }
+        };
+        let weight = |i: usize| match options.weighting {
+            NormalWeighting::Area => areas[i / 3],
+            NormalWeighting::Angle => face_normals[i / 3] * corner_angle(position, i),
+        };
+
+        // Triangle corners around each position.
+        let mut around: HashMap<[u32; 3], Vec<usize>> = HashMap::new();
+        for i in 0..triangles * 3 {
+            around.entry(key(position(i))).or_default().push(i);
+        }
+
+        let normals = (0..triangles * 3)
+            .map(|i| {
+                if !options.smooth {
+                    return face_normals[i / 3];
+                }
+
+                let normal = around[&key(position(i))]
+                    .iter()
+                    .filter(|&&other| smoothed(i / 3, other / 3))
+                    .fold(Vector3f::default(), |sum, &other| sum + weight(other));
+                unit(&normal)
+            })
+            .collect();
+
+        // Old tangents don't match new normals and split vertices.
+        let had_tangents = !self.tangents.is_empty();
+        self.tangents.clear();
+        self.set_corners(&corners, normals, Vec::new());
+        if had_tangents {
+            self.generate_tangents();
+        }
+    }
+
+    #[doc = "Replace tangents with ones calculated from UVs and normals."]
+    /// Tangents are generated by reference MikkTSpace implementation, so they
+    /// match normal maps baked by Blender, xNormal and Substance. They are
+    /// orthogonalized to normals with Gram-Schmidt, bitangent is
+    /// `w * cross(normal, tangent)`. Mirrored UVs and UV seams get separate
+    /// vertices. Where UVs have no direction, tangent is any one orthogonal
+    /// to normal. Mesh without UVs or normals gets no tangents.
+    pub fn generate_tangents(&mut self) {
+        self.tangents.clear();
+        if self.uvs.is_empty() || self.normals.is_empty() {
+            return;
+        }
+
+        let corners = self.corners();
+        let mut geometry = MikkTSpace {
+            mesh: self,
+            corners: &corners,
+            tangents: vec![Vector4f::default(); corners.len()],
+        };
+        // Error type has no values.
+        let _ = bevy_mikktspace::generate_tangents(&mut geometry);
+        let tangents = geometry.tangents;
+
+        self.set_corners(&corners, Vec::new(), tangents);
+    }
+
+    // Replace normals or tangents with ones of triangle corners, empty ones
+    // are kept. Indexed mesh gets vertex for each different corner of shared
+    // vertex.
+    fn set_corners(&mut self, corners: &[usize], normals: Vec<Vector3f>, tangents: Vec<Vector4f>) {
+        if self.indices.is_none() {
+            if !normals.is_empty() {
+                self.normals = normals;
+            }
+            if !tangents.is_empty() {
+                self.tangents = tangents;
+            }
+            return;
+        }
+
+        let normal = |i: usize| normals.get(i).or_else(|| self.normals.get(corners[i]));
+        let tangent = |i: usize| tangents.get(i).or_else(|| self.tangents.get(corners[i]));
+
+        let mut res = Mesh {
+            indices: Some(Vec::with_capacity(corners.len())),
+            ..Mesh::default()
+        };
+        let mut split = HashMap::new();
+        for (i, &v) in corners.iter().enumerate() {
+            let (n, t) = (normal(i), tangent(i));
+            let key = (
+                v,
+                n.map(|n| bits([n.0, n.1, n.2])),
+                t.map(|t| bits([t.0, t.1, t.2, t.3])),
+            );
+            let next = res.positions.len() as u32;
+            let index = *split.entry(key).or_insert_with(|| {
+                res.positions.push(self.positions[v]);
+                res.uvs.extend(self.uvs.get(v));
+                res.normals.extend(n);
+                res.tangents.extend(t);
+                next
+            });
+            res.indices.as_mut().unwrap().push(index);
+        }
+
+        self.positions = res.positions;
+        self.uvs = res.uvs;
+        self.normals = res.normals;
+        self.tangents = res.tangents;
+        self.indices = res.indices;
+    }
+
+    #[doc = "Number of indices per vertex, 1 for mesh without indices."]
+    /// It's how many times indexing reduced data sent to video memory.
+    pub fn compression_ratio(&self) -> f32 {
+        if self.positions.is_empty() {
+            return 1.0;
+        }
+
+        self.element_count() as f32 / self.positions.len() as f32
+    }
+
+    #[doc = "Indexed mesh with duplicated vertices merged."]
+    /// Vertex is merged with the first kept vertex which has all attributes
+    /// within `epsilon` from its ones. With zero `epsilon` only equal vertices
+    /// are merged, -0.0 is the same as 0.0.
+    pub fn to_indexed(&self, epsilon: f32) -> Mesh {
+        let attributes = |i: usize| {
+            let p = self.positions[i];
+            let uv = self.uvs.get(i).cloned().unwrap_or_default();
+            let n = self.normals.get(i).cloned().unwrap_or_default();
+            let t = self.tangents.get(i).cloned().unwrap_or_default();
+
+            [p.0, p.1, p.2, uv.0, uv.1, n.0, n.1, n.2, t.0, t.1, t.2, t.3]
+        };
+        // Close vertices are in the same or neighbouring cells of grid with
+        // `epsilon` step over positions.
+        let cell = |i: usize| {
+            let p = self.positions[i];
+            [p.0, p.1, p.2].map(|x| (x / epsilon).floor() as i64)
+        };
+        let close = |a: usize, b: usize| {
+            attributes(a)
+                .iter()
+                .zip(attributes(b).iter())
+                .all(|(x, y)| (x - y).abs() <= epsilon)
+        };
+
+        let corners = self.corners();
+        let mut res = Mesh {
+            indices: Some(Vec::with_capacity(corners.len())),
+            bounds: self.bounds,
+            submeshes: self.submeshes.clone(),
+            materials: self.materials.clone(),
+            smoothing_groups: self.smoothing_groups.clone(),
+            ..Mesh::default()
+        };
+        // Equal vertices by bits of attributes, or kept vertices in grid cell.
+        let mut unique = HashMap::new();
+        let mut cells: HashMap<[i64; 3], Vec<(u32, usize)>> = HashMap::new();
+        for i in corners {
+            let found = if epsilon > 0.0 {
+                let [x, y, z] = cell(i);
+                (0..27)
+                    .filter_map(|n| cells.get(&[x + n % 3 - 1, y + n / 3 % 3 - 1, z + n / 9 - 1]))
+                    .flatten()
+                    .filter(|&&(_, j)| close(i, j))
+                    .map(|&(index, _)| index)
+                    .min()
+            } else {
+                unique.get(&bits(attributes(i))).cloned()
+            };
+
+            let index = match found {
+                Some(index) => index,
+                None => {
+                    let index = res.positions.len() as u32;
+                    if epsilon > 0.0 {
+                        cells.entry(cell(i)).or_default().push((index, i));
+                    } else {
+                        unique.insert(bits(attributes(i)), index);
+                    }
+                    res.positions.push(self.positions[i]);
+                    if !self.uvs.is_empty() {
+                        res.uvs.push(self.uvs[i]);
+                    }
+                    if !self.normals.is_empty() {
+                        res.normals.push(self.normals[i]);
+                    }
+                    if !self.tangents.is_empty() {
+                        res.tangents.push(self.tangents[i]);
+                    }
+                    index
+                }
+            };
+            res.indices.as_mut().unwrap().push(index);
+        }
+
+        res
+    }
+
+    #[doc = "Indices as `u16`, if there are not more than 65536 vertices."]
+    pub fn indices_u16(&self) -> Option<Vec<u16>> {
+        if self.positions.len() > usize::from(u16::MAX) + 1 {
+            return None;
+        }
+
+        self.indices
+            .as_ref()
+            .map(|indices| indices.iter().map(|&i| i as u16).collect())
+    }
+
+    #[doc = "Create vertex array and buffers with mesh data."]
+    /// Needs current OpenGL context. Indices are sent as `u16` if possible.
+    pub fn upload(&self) -> MeshBuffers {
+        let mut vertex_array_id = 0;
+        let mut buffer_ids = Vec::new();
+        let mut index_type = None;
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut vertex_array_id);
+            gl::BindVertexArray(vertex_array_id);
+
+            buffer_ids.push(attribute_buffer(0, 3, &self.positions));
+            if !self.uvs.is_empty() {
+                buffer_ids.push(attribute_buffer(1, 2, &self.uvs));
+            }
+            if !self.normals.is_empty() {
+                buffer_ids.push(attribute_buffer(2, 3, &self.normals));
+            }
+            if !self.tangents.is_empty() {
+                buffer_ids.push(attribute_buffer(3, 4, &self.tangents));
+            }
+
+            // Element buffer binding is stored in vertex array.
+            if let Some(indices) = self.indices_u16() {
+                buffer_ids.push(buffer(gl::ELEMENT_ARRAY_BUFFER, &indices));
+                index_type = Some((gl::UNSIGNED_SHORT, std::mem::size_of::<u16>()));
+            } else if let Some(ref indices) = self.indices {
+                buffer_ids.push(buffer(gl::ELEMENT_ARRAY_BUFFER, indices));
+                index_type = Some((gl::UNSIGNED_INT, std::mem::size_of::<u32>()));
+            }
+
+            gl::BindVertexArray(0);
+        }
+
+        MeshBuffers {
+            vertex_array_id,
+            buffer_ids,
+            index_type,
+            element_count: self.element_count(),
+            submeshes: self.submeshes.clone(),
+        }
+    }
+}
+
+unsafe fn buffer<T>(target: GLenum, data: &[T]) -> GLuint {
+    let mut buffer_id = 0;
+
+    gl::GenBuffers(1, &mut buffer_id);
+    gl::BindBuffer(target, buffer_id);
+    gl::BufferData(
+        target,
+        std::mem::size_of_val(data) as isize,
+        data.as_ptr() as *const GLvoid,
+        gl::STATIC_DRAW,
+    );
+
+    buffer_id
+}
+
+unsafe fn attribute_buffer<T>(index: GLuint, size: GLint, data: &[T]) -> GLuint {
+    let buffer_id = buffer(gl::ARRAY_BUFFER, data);
+
+    gl::EnableVertexAttribArray(index);
+    gl::VertexAttribPointer(index, size, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+
+    buffer_id
+}
+
+#[doc = "Mesh in video memory."]
+/// Positions, UVs, normals and tangents are bound to attributes 0, 1, 2 and 3
+/// of vertex array.
+pub struct MeshBuffers {
+    vertex_array_id: GLuint,
+    buffer_ids: Vec<GLuint>,
+    // Type and size of index.
+    index_type: Option<(GLenum, usize)>,
+    element_count: usize,
+    submeshes: Vec<Submesh>,
+}
+
+impl MeshBuffers {
+    #[doc = "Vertex array object id."]
+    pub fn vertex_array_id(&self) -> GLuint {
+        self.vertex_array_id
+    }
+
+    #[doc = "Parts of mesh."]
+    pub fn submeshes(&self) -> &[Submesh] {
+        &self.submeshes
+    }
+
+    #[doc = "Draw whole mesh."]
+    pub fn draw(&self) {
+        self.draw_range(0..self.element_count);
+    }
+
+    #[doc = "Draw one part of mesh."]
+    pub fn draw_submesh(&self, index: usize) {
+        self.draw_range(self.submeshes[index].range.clone());
+    }
+
+    fn draw_range(&self, range: Range<usize>) {
+        unsafe {
+            gl::BindVertexArray(self.vertex_array_id);
+
+            match self.index_type {
+                Some((index_type, index_size)) => gl::DrawElements(
+                    gl::TRIANGLES,
+                    range.len() as GLsizei,
+                    index_type,
+                    (range.start * index_size) as *const GLvoid,
+                ),
+                None => gl::DrawArrays(gl::TRIANGLES, range.start as GLint, range.len() as GLsizei),
+            }
+
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for MeshBuffers {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(self.buffer_ids.len() as GLsizei, self.buffer_ids.as_ptr());
+            gl::DeleteVertexArrays(1, &self.vertex_array_id);
+        }
+    }
+}
+
+#[doc = "Build mesh vertex by vertex."]
+/// If any triangle is added, mesh is indexed and vertices are drawn only by
+/// triangles. Otherwise every 3 vertices form a triangle. Missing UVs and
+/// normals are zero, unless all vertices miss them.
+#[derive(Clone, Debug, Default)]
+pub struct MeshBuilder {
+    mesh: Mesh,
+    indices: Vec<u32>,
+    has_uvs: bool,
+    has_normals: bool,
+    parts: Vec<Part>,
+    // Smoothing group of each vertex and of each indexed triangle.
+    smoothing_group: Option<u32>,
+    vertex_groups: Vec<u32>,
+    triangle_groups: Vec<u32>,
+}
+
+// Start of submesh.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Part {
+    vertex: usize,
+    index: usize,
+    name: Option<String>,
+    material: Option<usize>,
+}
+
+impl MeshBuilder {
+    #[doc = "Create empty builder."]
+    pub fn new() -> MeshBuilder {
+        MeshBuilder::default()
+    }
+
+    #[doc = "Add vertex, returns its index."]
+    pub fn vertex(
+        &mut self,
+        position: Vector3f,
+        uv: Option<Vector2f>,
+        normal: Option<Vector3f>,
+    ) -> u32 {
+        let index = self.mesh.positions.len() as u32;
+
+        self.mesh.positions.push(position);
+        self.has_uvs |= uv.is_some();
+        self.mesh.uvs.push(uv.unwrap_or_default());
+        self.has_normals |= normal.is_some();
+        self.mesh.normals.push(normal.unwrap_or_default());
+        self.vertex_groups.push(self.smoothing_group.unwrap_or(0));
+
+        index
+    }
+
+    #[doc = "Add triangle of vertex indices, counter-clockwise."]
+    pub fn triangle(&mut self, a: u32, b: u32, c: u32) -> &mut MeshBuilder {
+        self.indices.extend_from_slice(&[a, b, c]);
+        self.triangle_groups.push(self.smoothing_group.unwrap_or(0));
+        self
+    }
+
+    #[doc = "Set smoothing group for the next triangles, 0 is flat."]
+    /// Triangles before the first group are flat.
+    pub fn smoothing_group(&mut self, group: u32) -> &mut MeshBuilder {
+        self.smoothing_group = Some(group);
+        self
+    }
+
+    #[doc = "Add material, returns its index."]
+    pub fn add_material(&mut self, material: Material) -> usize {
+        self.mesh.materials.push(material);
+        self.mesh.materials.len() - 1
+    }
+
+    #[doc = "Start new submesh, the previous one ends here."]
+    /// The first submesh starts implicitly. New submesh has the same name
+    /// and material.
+    pub fn submesh(&mut self) -> &mut MeshBuilder {
+        let part = self.part();
+        self.start(part)
+    }
+
+    #[doc = "Name the next triangles."]
+    /// Starts new submesh if name changes.
+    pub fn name(&mut self, name: Option<&str>) -> &mut MeshBuilder {
+        let part = self.part();
+        if part.name.as_deref() == name {
+            return self;
+        }
+
+        self.start(Part {
+            name: name.map(str::to_owned),
+            ..part
+        })
+    }
+
+    #[doc = "Use material for the next triangles."]
+    /// Starts new submesh if material changes.
+    pub fn material(&mut self, material: Option<usize>) -> &mut MeshBuilder {
+        let part = self.part();
+        if part.material == material {
+            return self;
+        }
+
+        self.start(Part { material, ..part })
+    }
+
+    // Current submesh properties at the current position.
+    fn part(&self) -> Part {
+        Part {
+            vertex: self.mesh.positions.len(),
+            index: self.indices.len(),
+            ..self.parts.last().cloned().unwrap_or_default()
+        }
+    }
+
+    fn start(&mut self, part: Part) -> &mut MeshBuilder {
+        match self.parts.last_mut() {
+            // Empty submesh is replaced.
+            Some(last) if (last.vertex, last.index) == (part.vertex, part.index) => *last = part,
+            _ => self.parts.push(part),
+        }
+        self
+    }
+
+    #[doc = "Finish mesh."]
+    pub fn build(mut self) -> Mesh {
+        let indexed = !self.indices.is_empty();
+        let element = |part: &Part| if indexed { part.index } else { part.vertex };
+
+        if self.parts.first().is_none_or(|p| (p.vertex, p.index) != (0, 0)) {
+            self.parts.insert(0, Part::default());
+        }
+        let end = self.part();
+        self.mesh.submeshes = self
+            .parts
+            .iter()
+            .zip(self.parts.iter().skip(1).chain(Some(&end)))
+            .map(|(part, next)| Submesh {
+                range: element(part)..element(next),
+                name: part.name.clone(),
+                material: part.material,
+            })
+            .filter(|submesh| !submesh.range.is_empty())
+            .collect();
+
+        if !self.has_uvs {
+            self.mesh.uvs.clear();
+        }
+        if !self.has_normals {
+            self.mesh.normals.clear();
+        }
+        if self.smoothing_group.is_some() {
+            self.mesh.smoothing_groups = if indexed {
+                self.triangle_groups
+            } else {
+                self.vertex_groups.chunks_exact(3).map(|t| t[0]).collect()
+            };
+        }
+        if indexed {
+            self.mesh.indices = Some(self.indices);
+        }
+        self.mesh.update_bounds();
+
+        self.mesh
+    }
+}
@@ -0,0 +1,542 @@
+#![doc = "Coherent noise for procedural textures and terrain."]
+//! All generators are deterministic for given seed on every platform, they
+//! don't depend on `rand`.
+
+#[doc = "Noise function of 2 and 3 coordinates."]
+pub trait Noise {
+    #[doc = "Value at 2D point."]
+    fn noise2(&self, x: f32, y: f32) -> f32;
+
+    #[doc = "Value at 3D point."]
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32;
+}
+
+impl<N: Noise + ?Sized> Noise for &N {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        (**self).noise2(x, y)
+    }
+
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+        (**self).noise3(x, y, z)
+    }
+}
+
+// SplitMix64 generator to shuffle permutation table.
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Permutation of 0..256 repeated twice, so sum of two values can index it.
+fn permutation(seed: u32) -> Vec<u8> {
+    let mut perm: Vec<u8> = (0..=255).collect();
+    let mut state = u64::from(seed);
+
+    // Fisher-Yates shuffle.
+    for i in (1..256).rev() {
+        let j = (split_mix(&mut state) % (i as u64 + 1)) as usize;
+        perm.swap(i, j);
+    }
+
+    let copy = perm.clone();
+    perm.extend(copy);
+    perm
+}
+
+// Integer hash of lattice point.
+fn hash(seed: u32, x: i32, y: i32, z: i32) -> u32 {
+    let mut h = seed
+        ^ (x as u32).wrapping_mul(0x8da6_b343)
+        ^ (y as u32).wrapping_mul(0xd816_3841)
+        ^ (z as u32).wrapping_mul(0xcb1a_b31f);
+
+    // Finalizer of MurmurHash3.
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+// Smooth step with zero first and second derivatives at ends.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+// Dot product of gradient selected by hash with offset.
+fn grad2(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+// Dot product of one of 12 cube edge gradients with offset.
+fn grad3(hash: u8, x: f32, y: f32, z: f32) -> f32 {
+    match hash & 15 {
+        0 | 12 => x + y,
+        1 | 14 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x + z,
+        5 => -x + z,
+        6 => x - z,
+        7 => -x - z,
+        8 => y + z,
+        9 | 13 => -y + z,
+        10 => y - z,
+        _ => -y - z,
+    }
+}
+
+// Integer part and fraction of coordinate.
+fn split(x: f32) -> (usize, f32) {
+    let f = x.floor();
+
+    ((f as i32 & 255) as usize, x - f)
+}
+
+#[doc = "Improved Perlin gradient noise, values are in `-1..1` range."]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Perlin {
+    perm: Vec<u8>,
+}
+
+impl Perlin {
+    #[doc = "Create noise with seed."]
+    pub fn new(seed: u32) -> Perlin {
+        Perlin {
+            perm: permutation(seed),
+        }
+    }
+}
+
+impl Noise for Perlin {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        let p = &self.perm;
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let (u, v) = (fade(xf), fade(yf));
+
+        let a = p[xi] as usize + yi;
+        let b = p[xi + 1] as usize + yi;
+
+        let res = lerp(
+            lerp(grad2(p[a], xf, yf), grad2(p[b], xf - 1.0, yf), u),
+            lerp(
+                grad2(p[a + 1], xf, yf - 1.0),
+                grad2(p[b + 1], xf - 1.0, yf - 1.0),
+                u,
+            ),
+            v,
+        );
+
+        res.clamp(-1.0, 1.0)
+    }
+
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let p = &self.perm;
+        let (xi, xf) = split(x);
+        let (yi, yf) = split(y);
+        let (zi, zf) = split(z);
+        let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+
+        let a = p[xi] as usize + yi;
+        let aa = p[a] as usize + zi;
+        let ab = p[a + 1] as usize + zi;
+        let b = p[xi + 1] as usize + yi;
+        let ba = p[b] as usize + zi;
+        let bb = p[b + 1] as usize + zi;
+
+        let res = lerp(
+            lerp(
+                lerp(grad3(p[aa], xf, yf, zf), grad3(p[ba], xf - 1.0, yf, zf), u),
+                lerp(
+                    grad3(p[ab], xf, yf - 1.0, zf),
+                    grad3(p[bb], xf - 1.0, yf - 1.0, zf),
+                    u,
+                ),
+                v,
+            ),
+            lerp(
+                lerp(
+                    grad3(p[aa + 1], xf, yf, zf - 1.0),
+                    grad3(p[ba + 1], xf - 1.0, yf, zf - 1.0),
+                    u,
+                ),
+                lerp(
+                    grad3(p[ab + 1], xf, yf - 1.0, zf - 1.0),
+                    grad3(p[bb + 1], xf - 1.0, yf - 1.0, zf - 1.0),
+                    u,
+                ),
+                v,
+            ),
+            w,
+        );
+
+        res.clamp(-1.0, 1.0)
+    }
+}
+
+#[doc = "Simplex noise, values are in `-1..1` range."]
+/// Has less directional artifacts than Perlin noise and it's faster in 3D.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simplex {
+    perm: Vec<u8>,
+}
+
+impl Simplex {
+    #[doc = "Create noise with seed."]
+    pub fn new(seed: u32) -> Simplex {
+        Simplex {
+            perm: permutation(seed),
+        }
+    }
+}
+
+// Contribution of simplex corner with offset.
+fn corner(radius: f32, d2: f32, g: f32) -> f32 {
+    let t = radius - d2;
+    if t < 0.0 {
+        0.0
+    } else {
+        let t2 = t * t;
+        t2 * t2 * g
+    }
+}
+
+impl Noise for Simplex {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        // Skew and unskew factors for 2D.
+        let f2 = 0.5 * (3.0f32.sqrt() - 1.0);
+        let g2 = (3.0 - 3.0f32.sqrt()) / 6.0;
+        let p = &self.perm;
+
+        let s = (x + y) * f2;
+        let (i, j) = ((x + s).floor(), (y + s).floor());
+        let t = (i + j) * g2;
+        let (x0, y0) = (x - (i - t), y - (j - t));
+
+        // Lower or upper triangle of skewed cell.
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let (x1, y1) = (x0 - i1 as f32 + g2, y0 - j1 as f32 + g2);
+        let (x2, y2) = (x0 - 1.0 + 2.0 * g2, y0 - 1.0 + 2.0 * g2);
+
+        let ii = (i as i32 & 255) as usize;
+        let jj = (j as i32 & 255) as usize;
+        let h0 = p[ii + p[jj] as usize];
+        let h1 = p[ii + i1 + p[jj + j1] as usize];
+        let h2 = p[ii + 1 + p[jj + 1] as usize];
+
+        let n = corner(0.5, x0 * x0 + y0 * y0, grad2(h0, x0, y0))
+            + corner(0.5, x1 * x1 + y1 * y1, grad2(h1, x1, y1))
+            + corner(0.5, x2 * x2 + y2 * y2, grad2(h2, x2, y2));
+
+        (70.0 * n).clamp(-1.0, 1.0)
+    }
+
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+        // Skew and unskew factors for 3D.
+        let f3 = 1.0 / 3.0;
+        let g3 = 1.0 / 6.0;
+        let p = &self.perm;
+
+        let s = (x + y + z) * f3;
+        let (i, j, k) = ((x + s).floor(), (y + s).floor(), (z + s).floor());
+        let t = (i + j + k) * g3;
+        let (x0, y0, z0) = (x - (i - t), y - (j - t), z - (k - t));
+
+        // Second and third corners of tetrahedron in skewed cell.
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let offset = |i: usize, j: usize, k: usize, n: f32| {
+            (
+                x0 - i as f32 + n * g3,
+                y0 - j as f32 + n * g3,
+                z0 - k as f32 + n * g3,
+            )
+        };
+        let c = [
+            (x0, y0, z0),
+            offset(i1, j1, k1, 1.0),
+            offset(i2, j2, k2, 2.0),
+            offset(1, 1, 1, 3.0),
+        ];
+        let steps = [(0, 0, 0), (i1, j1, k1), (i2, j2, k2), (1, 1, 1)];
+
+        let ii = (i as i32 & 255) as usize;
+        let jj = (j as i32 & 255) as usize;
+        let kk = (k as i32 & 255) as usize;
+
+        let mut n = 0.0;
+        for (&(dx, dy, dz), &(cx, cy, cz)) in steps.iter().zip(c.iter()) {
+            let h = p[ii + dx + p[jj + dy + p[kk + dz] as usize] as usize];
+            n += corner(0.6, cx * cx + cy * cy + cz * cz, grad3(h, cx, cy, cz));
+        }
+
+        (32.0 * n).clamp(-1.0, 1.0)
+    }
+}
+
+#[doc = "Worley cellular noise."]
+/// Value is distance to the nearest of random feature points, one point per
+/// unit cell. It's 0 at feature points and less than 1.5 in 2D and 1.8 in 3D.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Worley {
+    seed: u32,
+}
+
+impl Worley {
+    #[doc = "Create noise with seed."]
+    pub fn new(seed: u32) -> Worley {
+        Worley { seed }
+    }
+
+    // Feature point offset inside cell.
+    fn feature(&self, x: i32, y: i32, z: i32) -> (f32, f32, f32) {
+        let h = hash(self.seed, x, y, z);
+        let h2 = hash(h, z, x, y);
+
+        (
+            (h & 0xffff) as f32 / 65536.0,
+            (h >> 16) as f32 / 65536.0,
+            (h2 & 0xffff) as f32 / 65536.0,
+        )
+    }
+}
+
+impl Noise for Worley {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        let (xi, yi) = (x.floor() as i32, y.floor() as i32);
+        let mut min = f32::INFINITY;
+
+        for cy in yi - 1..=yi + 1 {
+            for cx in xi - 1..=xi + 1 {
+                let (fx, fy, _) = self.feature(cx, cy, 0);
+                let (dx, dy) = (cx as f32 + fx - x, cy as f32 + fy - y);
+                min = min.min(dx * dx + dy * dy);
+            }
+        }
+
+        min.sqrt()
+    }
+
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, yi, zi) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+        let mut min = f32::INFINITY;
+
+        for cz in zi - 1..=zi + 1 {
+            for cy in yi - 1..=yi + 1 {
+                for cx in xi - 1..=xi + 1 {
+                    let (fx, fy, fz) = self.feature(cx, cy, cz);
+                    let (dx, dy, dz) = (cx as f32 + fx - x, cy as f32 + fy - y, cz as f32 + fz - z);
+                    min = min.min(dx * dx + dy * dy + dz * dz);
+                }
+            }
+        }
+
+        min.sqrt()
+    }
+}
+
+#[doc = "Fractal Brownian motion, sum of noise octaves."]
+/// Each octave has `lacunarity` times higher frequency and `gain` times lower
+/// amplitude than the previous one. Sum is divided by sum of amplitudes, so
+/// range of source noise is kept.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fbm<N> {
+    #[doc = "Source noise."]
+    pub source: N,
+
+    #[doc = "Number of octaves."]
+    pub octaves: u32,
+
+    #[doc = "Frequency multiplier between octaves."]
+    pub lacunarity: f32,
+
+    #[doc = "Amplitude multiplier between octaves."]
+    pub gain: f32,
+}
+
+impl<N: Noise> Fbm<N> {
+    #[doc = "Create fBm with lacunarity 2 and gain 0.5."]
+    pub fn new(source: N, octaves: u32) -> Fbm<N> {
+        Fbm {
+            source,
+            octaves,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+
+    // Normalized sum of octaves, `f` gets octave frequency.
+    fn sum<F: Fn(f32) -> f32>(&self, f: F) -> f32 {
+        let (mut sum, mut max) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+
+        for _ in 0..self.octaves {
+            sum += f(frequency) * amplitude;
+            max += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+
+        if max > 0.0 {
+            sum / max
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<N: Noise> Noise for Fbm<N> {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        self.sum(|f| self.source.noise2(x * f, y * f))
+    }
+
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+        self.sum(|f| self.source.noise3(x * f, y * f, z * f))
+    }
+}
+
+#[doc = "Ridged multifractal, sharp ridges for mountain terrain."]
+/// Octaves of `(1 - |noise|)^2` are weighted by previous octave, so details
+/// gather on ridges. Source noise should be in `-1..1` range, result is in
+/// `-1..1` range too.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RidgedMulti<N> {
+    #[doc = "Source noise."]
+    pub source: N,
+
+    #[doc = "Number of octaves."]
+    pub octaves: u32,
+
+    #[doc = "Frequency multiplier between octaves."]
+    pub lacunarity: f32,
+
+    #[doc = "Amplitude multiplier between octaves."]
+    pub gain: f32,
+
+    #[doc = "Multiplier of previous octave to weight the next one."]
+    pub weight: f32,
+}
+
+impl<N: Noise> RidgedMulti<N> {
+    #[doc = "Create ridged multifractal with lacunarity 2, gain 0.5 and weight 2."]
+    pub fn new(source: N, octaves: u32) -> RidgedMulti<N> {
+        RidgedMulti {
+            source,
+            octaves,
+            lacunarity: 2.0,
+            gain: 0.5,
+            weight: 2.0,
+        }
+    }
+
+    // Weighted sum of ridged octaves, `f` gets octave frequency.
+    fn sum<F: Fn(f32) -> f32>(&self, f: F) -> f32 {
+        let (mut sum, mut max) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        let mut weight = 1.0;
+
+        for _ in 0..self.octaves {
+            let ridge = 1.0 - f(frequency).abs();
+            let signal = ridge * ridge * weight;
+            weight = (signal * self.weight).clamp(0.0, 1.0);
+
+            sum += signal * amplitude;
+            max += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+
+        if max > 0.0 {
+            sum / max * 2.0 - 1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<N: Noise> Noise for RidgedMulti<N> {
+    fn noise2(&self, x: f32, y: f32) -> f32 {
+        self.sum(|f| self.source.noise2(x * f, y * f))
+    }
+
+    fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+        self.sum(|f| self.source.noise3(x * f, y * f, z * f))
+    }
+}
+
+#[doc = "Fill single channel float image with noise."]
+/// Pixel `(i, j)` samples noise at `((i + 0.5) * scale, (j + 0.5) * scale)`,
+/// rows go from bottom to top like in `glTexImage2D`. Upload it as
+/// `GL_R32F` texture with `GL_RED` format and `GL_FLOAT` type. Incomplete
+/// last row is filled too, zero `width` leaves `pixels` untouched.
+pub fn fill_image_f32<N: Noise>(noise: &N, pixels: &mut [f32], width: usize, scale: f32) {
+    if width == 0 {
+        return;
+    }
+
+    for (j, row) in pixels.chunks_mut(width).enumerate() {
+        let y = (j as f32 + 0.5) * scale;
+        for (i, p) in row.iter_mut().enumerate() {
+            *p = noise.noise2((i as f32 + 0.5) * scale, y);
+        }
+    }
+}
+
+#[doc = "Fill 8-bit image with noise, `-1..1` range is mapped to `0..255`."]
+/// Image has `channels` components per pixel and each of them gets the same
+/// value, so it can be uploaded with `GL_RED`, `GL_RGB` or `GL_RGBA` format
+/// and `GL_UNSIGNED_BYTE` type. Sample points and incomplete rows are like in
+/// `fill_image_f32`, zero `width` or `channels` leaves `pixels` untouched.
+pub fn fill_image<N: Noise>(
+    noise: &N,
+    pixels: &mut [u8],
+    width: usize,
+    channels: usize,
+    scale: f32,
+) {
+    if width == 0 || channels == 0 {
+        return;
+    }
+
+    for (j, row) in pixels.chunks_mut(width * channels).enumerate() {
+        let y = (j as f32 + 0.5) * scale;
+        for (i, p) in row.chunks_mut(channels).enumerate() {
+            let v = noise.noise2((i as f32 + 0.5) * scale, y);
+            let c = ((v.clamp(-1.0, 1.0) + 1.0) * 127.5).round() as u8;
+            for x in p.iter_mut() {
+                *x = c;
+            }
+        }
+    }
+}
@@ -1,129 +1,577 @@
 #![doc = "Module for loading OBJ files."]
 
 use std::{
-    fs::File, io::{BufReader, Result}, path::Path, str::FromStr,
+    collections::HashMap, error::Error, fmt, fs::File, io::{self, BufRead, BufReader}, iter,
+    path::{Path, PathBuf},
 };
 
+use color::Rgb;
+
 use matrix::Vector3f;
 
-#[doc = "2D vector for UV data."]
-#[derive(Clone, Debug)]
-#[repr(C)]
-pub struct Vector2f(f32, f32);
+use mesh::{Material, Mesh, MeshBuilder, NormalOptions};
 
-#[doc = "Load obj file."]
-/// Set invert_v if use DDS texture.
-pub fn obj_load<P: AsRef<Path>>(
-    path: P,
-    out_vertices: &mut Vec<Vector3f>,
-    out_uvs: &mut Vec<Vector2f>,
-    out_normals: &mut Vec<Vector3f>,
-    invert_v: bool,
-) -> Result<()> {
-    let mut vertex_indices = Vec::<usize>::new();
-    let mut uv_indices = Vec::<usize>::new();
-    let mut normal_indices = Vec::<usize>::new();
-
-    let mut temp_vertices = Vec::new();
-    let mut temp_uvs = Vec::new();
-    let mut temp_normals = Vec::new();
-
-    let f = File::open(path)?;
-    let f = BufReader::new(f);
+pub use matrix::Vector2f;
+
+#[doc = "Reason of OBJ loading error."]
+#[derive(Debug)]
+pub enum ObjErrorKind {
+    #[doc = "File can't be read."]
+    Io(io::Error),
+
+    #[doc = "Coordinate isn't a float number."]
+    BadFloat(String),
+
+    #[doc = "Index isn't an integer number."]
+    BadIndex(String),
+
+    #[doc = "Required component is missing."]
+    MissingComponent(&'static str),
+
+    #[doc = "Index refers to element which isn't defined."]
+    IndexOutOfRange(i64),
+
+    #[doc = "Token isn't a directive of OBJ format."]
+    UnsupportedDirective(String),
+
+    #[doc = "Error in material library."]
+    Material(Box<ObjError>),
+}
+
+impl fmt::Display for ObjErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ObjErrorKind::Io(ref e) => write!(f, "{}", e),
+            ObjErrorKind::BadFloat(ref s) => write!(f, "bad float `{}`", s),
+            ObjErrorKind::BadIndex(ref s) => write!(f, "bad index `{}`", s),
+            ObjErrorKind::MissingComponent(what) => write!(f, "missing {}", what),
+            ObjErrorKind::IndexOutOfRange(i) => write!(f, "index {} out of range", i),
+            ObjErrorKind::UnsupportedDirective(ref s) => write!(f, "unsupported directive `{}`", s),
+            ObjErrorKind::Material(ref e) => write!(f, "in material library: {}", e),
+        }
+    }
+}
+
+#[doc = "Error of OBJ loading with its location."]
+#[derive(Debug)]
+pub struct ObjError {
+    #[doc = "Path of file."]
+    pub file: PathBuf,
+
+    #[doc = "Line number starting from 1, 0 if file can't be opened."]
+    pub line: usize,
+
+    #[doc = "Column in bytes starting from 1, 0 for whole line."]
+    pub column: usize,
+
+    #[doc = "Reason of error."]
+    pub kind: ObjErrorKind,
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file.display(),
+            self.line,
+            self.column,
+            self.kind
+        )
+    }
+}
+
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.kind {
+            ObjErrorKind::Io(ref e) => Some(e),
+            ObjErrorKind::Material(ref e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+// Error with column inside line.
+type ParseResult<T> = Result<T, (usize, ObjErrorKind)>;
+
+// Whitespace separated tokens of line with their columns.
+struct Tokens<'a> {
+    line: &'a str,
+    pos: usize,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        let start = self.pos + self.line[self.pos..].find(|c: char| !c.is_whitespace())?;
+        let end = self.line[start..]
+            .find(char::is_whitespace)
+            .map_or(self.line.len(), |len| start + len);
+        self.pos = end;
+
+        Some((start + 1, &self.line[start..end]))
+    }
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: &'a str) -> Tokens<'a> {
+        Tokens { line, pos: 0 }
+    }
+
+    fn required(&mut self, what: &'static str) -> ParseResult<(usize, &'a str)> {
+        let column = self.line.len() + 1;
+        self.next()
+            .ok_or((column, ObjErrorKind::MissingComponent(what)))
+    }
+
+    fn float(&mut self, what: &'static str) -> ParseResult<f32> {
+        let (column, token) = self.required(what)?;
+        token
+            .parse()
+            .map_err(|_| (column, ObjErrorKind::BadFloat(token.to_owned())))
+    }
+
+    // The rest of line, names can contain spaces.
+    fn rest(&mut self, what: &'static str) -> ParseResult<(usize, &'a str)> {
+        let (column, _) = self.required(what)?;
+        let rest = self.line[column - 1..].trim_end();
+        self.pos = self.line.len();
+
+        Ok((column, rest))
+    }
+}
+
+// Zero based index of element in list of `count` elements.
+fn index(column: usize, token: &str, count: usize) -> ParseResult<usize> {
+    let i: i64 = token
+        .parse()
+        .map_err(|_| (column, ObjErrorKind::BadIndex(token.to_owned())))?;
+    // Negative indices are relative to the end of list.
+    let res = if i < 0 { count as i64 + i } else { i - 1 };
+    if res < 0 || res >= count as i64 {
+        return Err((column, ObjErrorKind::IndexOutOfRange(i)));
+    }
+
+    Ok(res as usize)
+}
+
+#[doc = "Method of splitting polygons into triangles."]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Triangulation {
+    #[doc = "Triangles around the first vertex, only for convex polygons."]
+    #[default]
+    Fan,
 
-    use std::io::BufRead;
-    for line in f.lines() {
-        match line {
-            Ok(ref l) => {
-                let mut split = l.split_whitespace();
-                if let Some(hdr) = split.next() {
-                    match hdr {
-                        "v" => {
-                            let vertex = Vector3f(
-                                FromStr::from_str(split.next().expect("vertex x"))
-                                    .expect("vertex x"),
-                                FromStr::from_str(split.next().expect("vertex y"))
-                                    .expect("vertex y"),
-                                FromStr::from_str(split.next().expect("vertex z"))
-                                    .expect("vertex z"),
-                            );
-                            println!("[OBJ]Vertex: {:?}", &vertex);
-                            temp_vertices.push(vertex);
-                        }
-                        "vt" => {
-                            let uv = Vector2f(
-                                FromStr::from_str(split.next().expect("vertex u"))
-                                    .expect("vertex u"),
-                                (if invert_v { -1f32 } else { 1f32 })
-                                    * f32::from_str(split.next().expect("vertex v"))
-                                        .expect("vertex v"),
-                            );
-                            println!("[OBJ]UV: {:?}", &uv);
-                            temp_uvs.push(uv);
-                        }
-                        "vn" => {
-                            let normal = Vector3f(
-                                FromStr::from_str(split.next().expect("normal x"))
-                                    .expect("normal x"),
-                                FromStr::from_str(split.next().expect("normal y"))
-                                    .expect("normal y"),
-                                FromStr::from_str(split.next().expect("normal z"))
-                                    .expect("normal z"),
-                            );
-                            println!("[OBJ]Normal: {:?}", &normal);
-                            temp_normals.push(normal);
-                        }
-                        "f" => {
-                            let mut vertex_index: [usize; 3] = [0; 3];
-                            let mut uv_index: [usize; 3] = [0; 3];
-                            let mut normal_index: [usize; 3] = [0; 3];
-
-                            // non-inclusive range
-                            for i in 0..3 {
-                                let mut split_face = split.next().expect("face").split('/');
-                                vertex_index[i] = FromStr::from_str(
-                                    split_face.next().expect("face v"),
-                                ).expect("face v");
-                                uv_index[i] = FromStr::from_str(split_face.next().expect("face u"))
-                                    .expect("face u");
-                                normal_index[i] = FromStr::from_str(
-                                    split_face.next().expect("face n"),
-                                ).expect("face n");
-                                println!(
-                                    "[OBJ] Face {}: v{} u{} n{}",
-                                    i, vertex_index[i], uv_index[i], normal_index[i]
-                                );
-                            }
-
-                            vertex_indices.extend_from_slice(&vertex_index);
-                            uv_indices.extend_from_slice(&uv_index);
-                            normal_indices.extend_from_slice(&normal_index);
-                        }
-                        _ => {}
+    #[doc = "Ear clipping, for any simple polygons."]
+    EarClipping,
+}
+
+#[doc = "Options of OBJ loading."]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjOptions {
+    #[doc = "Method of splitting faces with more than 3 vertices."]
+    pub triangulation: Triangulation,
+
+    #[doc = "Generation of normals missing in file."]
+    /// `None` leaves them zero, or mesh without normals if file has none.
+    pub normals: Option<NormalOptions>,
+}
+
+impl Default for ObjOptions {
+    fn default() -> ObjOptions {
+        ObjOptions {
+            triangulation: Triangulation::default(),
+            normals: Some(NormalOptions::default()),
+        }
+    }
+}
+
+// Directives of OBJ format which are valid but skipped: points, lines,
+// free-form geometry and render attributes.
+const IGNORED_DIRECTIVES: &[&str] = &[
+    "vp", "p", "l", "cstype", "deg", "bmat", "step", "curv", "curv2", "surf", "parm", "trim",
+    "hole", "scrv", "sp", "end", "con", "mg", "bevel", "c_interp", "d_interp", "lod",
+    "shadow_obj", "trace_obj", "ctech", "stech",
+];
+
+fn fan(count: usize) -> Vec<[usize; 3]> {
+    (1..count - 1).map(|i| [0, i, i + 1]).collect()
+}
+
+// Polygon can be non-planar, so it's checked against its average normal.
+fn ear_clip(points: &[Vector3f]) -> Vec<[usize; 3]> {
+    // Newell's method.
+    let mut normal = Vector3f::default();
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        normal += Vector3f(
+            (a.1 - b.1) * (a.2 + b.2),
+            (a.2 - b.2) * (a.0 + b.0),
+            (a.0 - b.0) * (a.1 + b.1),
+        );
+    }
+    // Positive if `c` is to the left of `a -> b`.
+    let side = |a: usize, b: usize, c: usize| {
+        (points[b] - points[a])
+            .cross(&(points[c] - points[a]))
+            .dot(&normal)
+    };
+
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut res = Vec::with_capacity(points.len() - 2);
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let corner = |i: usize| {
+            [
+                remaining[(i + count - 1) % count],
+                remaining[i],
+                remaining[(i + 1) % count],
+            ]
+        };
+        let is_ear = |i: usize| {
+            let [a, b, c] = corner(i);
+            side(a, b, c) > 0.0
+                && remaining.iter().all(|&p| {
+                    p == a
+                        || p == b
+                        || p == c
+                        || side(a, b, p) < 0.0
+                        || side(b, c, p) < 0.0
+                        || side(c, a, p) < 0.0
+                })
+        };
+
+        // Degenerate polygons have no ears, they are clipped anyway.
+        let ear = (0..count).find(|&i| is_ear(i)).unwrap_or(0);
+        res.push(corner(ear));
+        remaining.remove(ear);
+    }
+    res.push([remaining[0], remaining[1], remaining[2]]);
+
+    res
+}
+
+// Indices of position, UV and normal.
+type FaceVertex = (usize, Option<usize>, Option<usize>);
+
+struct Parser {
+    options: ObjOptions,
+    dir: PathBuf,
+    positions: Vec<Vector3f>,
+    uvs: Vec<Vector2f>,
+    normals: Vec<Vector3f>,
+    // Whether each vertex of mesh misses normal.
+    missing_normals: Vec<bool>,
+    materials: HashMap<String, usize>,
+    object: Option<String>,
+    builder: MeshBuilder,
+}
+
+impl Parser {
+    fn new(path: &Path, options: &ObjOptions) -> Parser {
+        Parser {
+            options: options.clone(),
+            dir: path.parent().unwrap_or_else(|| Path::new("")).to_owned(),
+            positions: Vec::new(),
+            uvs: Vec::new(),
+            normals: Vec::new(),
+            missing_normals: Vec::new(),
+            materials: HashMap::new(),
+            object: None,
+            builder: MeshBuilder::new(),
+        }
+    }
+
+    fn line(&mut self, line: &str) -> ParseResult<()> {
+        let mut tokens = Tokens::new(line);
+        let (column, hdr) = match tokens.next() {
+            Some(token) => token,
+            None => return Ok(()),
+        };
+
+        match hdr {
+            "v" => {
+                let vertex = Vector3f(
+                    tokens.float("vertex x")?,
+                    tokens.float("vertex y")?,
+                    tokens.float("vertex z")?,
+                );
+                self.positions.push(vertex);
+            }
+            "vt" => {
+                let uv = Vector2f(tokens.float("vertex u")?, tokens.float("vertex v")?);
+                self.uvs.push(uv);
+            }
+            "vn" => {
+                let normal = Vector3f(
+                    tokens.float("normal x")?,
+                    tokens.float("normal y")?,
+                    tokens.float("normal z")?,
+                );
+                self.normals.push(normal);
+            }
+            "f" => {
+                let mut face = Vec::new();
+                for (column, token) in tokens {
+                    face.push(self.face_vertex(column, token)?);
+                }
+                if face.len() < 3 {
+                    return Err((
+                        line.len() + 1,
+                        ObjErrorKind::MissingComponent("face vertex"),
+                    ));
+                }
+
+                let triangles = match self.options.triangulation {
+                    Triangulation::Fan => fan(face.len()),
+                    Triangulation::EarClipping => {
+                        let points: Vec<Vector3f> =
+                            face.iter().map(|&(v, _, _)| self.positions[v]).collect();
+                        ear_clip(&points)
+                    }
+                };
+                for triangle in triangles {
+                    for &i in triangle.iter() {
+                        let (v, vt, vn) = face[i];
+                        self.builder.vertex(
+                            self.positions[v],
+                            vt.map(|vt| self.uvs[vt]),
+                            vn.map(|vn| self.normals[vn]),
+                        );
+                        self.missing_normals.push(vn.is_none());
                     }
                 }
             }
-            Err(e) => {
-                return Err(e);
+            "mtllib" => {
+                let first = tokens.required("material library")?;
+                for (column, name) in iter::once(first).chain(tokens) {
+                    let materials = match mtl_load(self.dir.join(name)) {
+                        Ok(materials) => materials,
+                        // Models are often shared without their materials.
+                        Err(ObjError {
+                            kind: ObjErrorKind::Io(ref e),
+                            line: 0,
+                            ..
+                        }) if e.kind() == io::ErrorKind::NotFound => continue,
+                        Err(e) => return Err((column, ObjErrorKind::Material(Box::new(e)))),
+                    };
+
+                    for material in materials {
+                        let name = material.name.clone();
+                        let index = self.builder.add_material(material);
+                        self.materials.entry(name).or_insert(index);
+                    }
+                }
+            }
+            "usemtl" => {
+                let (_, name) = tokens.rest("material name")?;
+                self.builder.material(self.materials.get(name).cloned());
             }
+            "o" => {
+                let (_, name) = tokens.rest("object name")?;
+                self.object = Some(name.to_owned());
+                self.builder.name(Some(name));
+            }
+            // Unnamed group goes back to the object.
+            "g" => {
+                let name = match tokens.rest("group name") {
+                    Ok((_, name)) => Some(name),
+                    Err(_) => self.object.as_deref(),
+                };
+                self.builder.name(name);
+            }
+            "s" => {
+                let (column, token) = tokens.required("smoothing group")?;
+                let group = match token {
+                    "off" => 0,
+                    _ => token
+                        .parse()
+                        .map_err(|_| (column, ObjErrorKind::BadIndex(token.to_owned())))?,
+                };
+                self.builder.smoothing_group(group);
+            }
+            _ if hdr.starts_with('#') || IGNORED_DIRECTIVES.contains(&hdr) => {}
+            _ => return Err((column, ObjErrorKind::UnsupportedDirective(hdr.to_owned()))),
         }
-    }
 
-    for vertex_idx in vertex_indices {
-        out_vertices.push(temp_vertices[vertex_idx - 1].clone());
+        Ok(())
     }
 
-    for normal_idx in normal_indices {
-        out_normals.push(temp_normals[normal_idx - 1].clone());
+    // Indices of `v`, `v/vt`, `v//vn` or `v/vt/vn` face vertex.
+    fn face_vertex(&self, column: usize, token: &str) -> ParseResult<FaceVertex> {
+        let counts = [self.positions.len(), self.uvs.len(), self.normals.len()];
+        let names = ["vertex index", "texture index", "normal index"];
+
+        let parts: Vec<&str> = token.split('/').collect();
+        if parts.len() > 3 {
+            return Err((column, ObjErrorKind::BadIndex(token.to_owned())));
+        }
+
+        // Missing parts are reported after the end of token.
+        let end = column + token.len();
+        let mut res = [None; 3];
+        let mut column = column;
+        for (i, part) in parts.iter().enumerate() {
+            // Only texture index can be skipped, and only before normal index.
+            if !part.is_empty() {
+                res[i] = Some(index(column, part, counts[i])?);
+            } else if i != 1 || parts.len() != 3 {
+                return Err((column.min(end), ObjErrorKind::MissingComponent(names[i])));
+            }
+            column += part.len() + 1;
+        }
+
+        Ok((res[0].unwrap(), res[1], res[2]))
     }
+}
 
-    for uv_idx in uv_indices {
-        out_uvs.push(temp_uvs[uv_idx - 1].clone());
+#[doc = "Load obj file with default options."]
+/// Faces can be `v`, `v/vt`, `v//vn` or `v/vt/vn` with any number of
+/// vertices. UVs missing only on some faces are filled with zeros, if whole
+/// file misses them, UVs are empty. Missing normals are generated.
+/// Mesh isn't indexed, `o`, `g` and `usemtl` start new submesh. Material
+/// libraries are relative to OBJ file, missing libraries and materials are
+/// skipped. Smoothing groups are used by `Mesh::generate_normals`. Use
+/// `Mesh::invert_v` for DDS textures. Points, lines, free-form geometry and
+/// other OBJ directives without triangles are skipped, unknown ones are
+/// errors.
+pub fn obj_load<P: AsRef<Path>>(path: P) -> Result<Mesh, ObjError> {
+    obj_load_with(path, &ObjOptions::default())
+}
+
+#[doc = "Load obj file."]
+pub fn obj_load_with<P: AsRef<Path>>(path: P, options: &ObjOptions) -> Result<Mesh, ObjError> {
+    let path = path.as_ref();
+    let mut parser = Parser::new(path, options);
+    parse_lines(path, |line| parser.line(line))?;
+
+    let mut mesh = parser.builder.build();
+    if let Some(ref normals) = options.normals {
+        if parser.missing_normals.contains(&true) {
+            let mut generated = mesh.clone();
+            generated.generate_normals_with(normals);
+
+            // Normals from file are kept.
+            if mesh.normals.is_empty() {
+                mesh.normals = generated.normals;
+            } else {
+                let normals = mesh.normals.iter_mut().zip(generated.normals);
+                for ((n, g), &missing) in normals.zip(&parser.missing_normals) {
+                    if missing {
+                        *n = g;
+                    }
+                }
+            }
+        }
     }
 
-    for outuv in out_uvs {
-        println!("YV[]: {:?}", outuv);
+    Ok(mesh)
+}
+
+// Call `parse` for each line of file.
+fn parse_lines<F>(path: &Path, mut parse: F) -> Result<(), ObjError>
+where
+    F: FnMut(&str) -> ParseResult<()>,
+{
+    let error = |line, (column, kind)| ObjError {
+        file: path.to_owned(),
+        line,
+        column,
+        kind,
+    };
+
+    let f = File::open(path).map_err(|e| error(0, (0, ObjErrorKind::Io(e))))?;
+    let f = BufReader::new(f);
+
+    for (i, line) in f.lines().enumerate() {
+        let line = line.map_err(|e| error(i + 1, (0, ObjErrorKind::Io(e))))?;
+        parse(&line).map_err(|e| error(i + 1, e))?;
     }
 
     Ok(())
 }
+
+// Texture path after options like `-s 1 1 1`.
+fn texture_path(tokens: &mut Tokens, dir: &Path) -> ParseResult<PathBuf> {
+    let (column, rest) = tokens.rest("texture path")?;
+    // Paths with spaces are supported only without options.
+    let path = if rest.starts_with('-') {
+        Tokens::new(rest).last().map_or(rest, |(_, token)| token)
+    } else {
+        rest
+    };
+    if path.starts_with('-') {
+        return Err((column + rest.len(), ObjErrorKind::MissingComponent("texture path")));
+    }
+
+    Ok(dir.join(path))
+}
+
+// Color with optional green and blue, they are equal to red if missing.
+fn color(tokens: &mut Tokens) -> ParseResult<Rgb> {
+    let r = tokens.float("red")?;
+    match tokens.next() {
+        None => Ok(Rgb::new(r, r, r)),
+        Some((column, g)) => {
+            let g = g
+                .parse()
+                .map_err(|_| (column, ObjErrorKind::BadFloat(g.to_owned())))?;
+            Ok(Rgb::new(r, g, tokens.float("blue")?))
+        }
+    }
+}
+
+#[doc = "Load MTL material library."]
+/// Texture paths are relative to MTL file. Only colors, `Ns`, `d`, `Tr`,
+/// `illum`, `map_Kd`, `map_Bump` and `map_Ks` are loaded, other directives
+/// are ignored.
+pub fn mtl_load<P: AsRef<Path>>(path: P) -> Result<Vec<Material>, ObjError> {
+    let path = path.as_ref();
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut materials: Vec<Material> = Vec::new();
+
+    parse_lines(path, |line| {
+        let mut tokens = Tokens::new(line);
+        let (column, hdr) = match tokens.next() {
+            Some(token) => token,
+            None => return Ok(()),
+        };
+        if hdr == "newmtl" {
+            let (_, name) = tokens.rest("material name")?;
+            materials.push(Material::new(name));
+            return Ok(());
+        }
+        if hdr.starts_with('#') {
+            return Ok(());
+        }
+
+        let material = materials
+            .last_mut()
+            .ok_or((column, ObjErrorKind::MissingComponent("newmtl")))?;
+        match hdr {
+            "Ka" => material.ambient = color(&mut tokens)?,
+            "Kd" => material.diffuse = color(&mut tokens)?,
+            "Ks" => material.specular = color(&mut tokens)?,
+            "Ns" => material.shininess = tokens.float("specular exponent")?,
+            "d" => material.opacity = tokens.float("opacity")?,
+            "Tr" => material.opacity = 1.0 - tokens.float("transparency")?,
+            "illum" => {
+                let (column, token) = tokens.required("illumination model")?;
+                material.illumination = token
+                    .parse()
+                    .map_err(|_| (column, ObjErrorKind::BadIndex(token.to_owned())))?;
+            }
+            "map_Kd" => material.diffuse_map = Some(texture_path(&mut tokens, dir)?),
+            "map_Bump" | "map_bump" | "bump" => {
+                material.bump_map = Some(texture_path(&mut tokens, dir)?)
+            }
+            "map_Ks" => material.specular_map = Some(texture_path(&mut tokens, dir)?),
+            _ => {}
+        }
+
+        Ok(())
+    })?;
+
+    Ok(materials)
+}
@@ -9,6 +9,12 @@ use gl;
 use gl::types::{GLchar, GLenum, GLsizei, GLuint, GLvoid};
 use std::ffi::CStr;
 
+#[doc = "Width of window in pixels."]
+pub const WINDOW_WIDTH: u32 = 1024;
+
+#[doc = "Height of window in pixels."]
+pub const WINDOW_HEIGHT: u32 = 768;
+
 #[doc = "Context of SDL2."]
 pub struct SdlContext {
     #[doc = "SDL2 itself"]
@@ -62,7 +68,7 @@ impl SdlContext {
             .set_context_profile(sdl2::video::GLProfile::Core);
 
         let window = sdl_vs_context
-            .window(window_name, 1024, 768)
+            .window(window_name, WINDOW_WIDTH, WINDOW_HEIGHT)
             .position_centered()
             .opengl()
             .build()
@@ -0,0 +1,120 @@
+use gl::{
+    self,
+    types::{GLint, GLuint},
+};
+
+use sdl2;
+
+use tutcommon::{controls::Controls, glutils, matrix::Matrix4f, mesh::MeshBuffers, objloader};
+
+#[doc = "Moved out drawing GL stuff to avoid mess with the other code."]
+pub struct GLScene {
+    mesh: MeshBuffers,         // VAO, VBOs and index buffer.
+    program_id: GLuint,        // Shader program id.
+    texture_id: GLuint,        // Texture id.
+    matrix_uniform_id: GLint,  // MVP uniform location.
+    view_uniform_id: GLint,    // V uniform location.
+    model_uniform_id: GLint,   // M uniform location.
+    light_uniform_id: GLint,   // LightPosition_worldspace uniform location.
+    texture_uniform_id: GLint, // myTextureSampler uniform location.
+}
+
+impl GLScene {
+    #[doc = "Create scene and init it."]
+    pub fn new(vs: &sdl2::VideoSubsystem) -> GLScene {
+        // Create and compile our GLSL program from the shaders
+        let program_id = glutils::load_program(
+            "data/tut08/StandardShading.vertexshader",
+            "data/tut08/StandardShading.fragmentshader",
+        );
+
+        let mut mesh = objloader::obj_load("data/tut08/suzanne.obj").expect("Load obj");
+        mesh.invert_v();
+
+        // Send each unique vertex once.
+        let mesh = mesh.to_indexed(1e-4);
+        println!(
+            "Vertex count: {}, index count: {}, compression ratio: {:.2}",
+            mesh.vertex_count(),
+            mesh.element_count(),
+            mesh.compression_ratio()
+        );
+
+        let (matrix_uniform_id, view_uniform_id, model_uniform_id, light_uniform_id) = unsafe {
+            // Get a handle for our uniforms
+            (
+                gl::GetUniformLocation(program_id, "MVP\x00".as_ptr() as *const i8),
+                gl::GetUniformLocation(program_id, "V\x00".as_ptr() as *const i8),
+                gl::GetUniformLocation(program_id, "M\x00".as_ptr() as *const i8),
+                gl::GetUniformLocation(
+                    program_id,
+                    "LightPosition_worldspace\x00".as_ptr() as *const i8,
+                ),
+            )
+        };
+
+        let texture_uniform_id = unsafe {
+            gl::GetUniformLocation(program_id, "myTextureSampler\x00".as_ptr() as *const i8)
+        };
+
+        // Send vertices, UVs, normals and indices to buffers.
+        let mesh = mesh.upload();
+
+        let texture_id = glutils::load_dds_texture(vs, "data/tut08/uvmap.DDS").unwrap();
+
+        GLScene {
+            mesh,
+            program_id,
+            texture_id,
+            matrix_uniform_id,
+            view_uniform_id,
+            model_uniform_id,
+            light_uniform_id,
+            texture_uniform_id,
+        }
+    }
+
+    #[doc = "Update data each frame."]
+    pub fn update(&mut self) {}
+
+    #[doc = "Render scene each frame."]
+    pub fn draw(&self, controls: &Controls) {
+        // Model matrix : an identity matrix (model will be at the origin)
+        let model = Matrix4f::default();
+
+        let mvp = controls.projection.mul(&controls.view).mul(&model);
+
+        unsafe {
+            // Use our shader
+            gl::UseProgram(self.program_id);
+
+            // Send our transformation to the currently bound shader,
+            // in the "MVP" uniform.
+            gl::UniformMatrix4fv(self.matrix_uniform_id, 1, gl::FALSE, mvp.as_ptr());
+            gl::UniformMatrix4fv(self.model_uniform_id, 1, gl::FALSE, model.as_ptr());
+            gl::UniformMatrix4fv(self.view_uniform_id, 1, gl::FALSE, controls.view.as_ptr());
+
+            gl::Uniform3f(self.light_uniform_id, 4.0, 4.0, 4.0);
+
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture_id);
+            // Set our "myTextureSampler" sampler to user Texture Unit 0
+            gl::Uniform1i(self.texture_uniform_id, 0);
+
+            // Draw the triangles with glDrawElements, index buffer is bound
+            // in vertex array of the mesh.
+            self.mesh.draw();
+        }
+    }
+}
+
+#[doc = "Always clean up after yourself."]
+impl Drop for GLScene {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteProgram(self.program_id);
+
+            gl::DeleteTextures(1, &self.texture_id);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use gl::{
+    self,
+    types::{GLint, GLuint, GLvoid},
+};
+
+use sdl2;
+
+use tutcommon::{
+    controls::Controls,
+    glutils,
+    matrix::{Matrix4f, Vector3f},
+    mesh::MeshBuffers,
+    noise::{self, Fbm, Perlin},
+    objloader,
+};
+
+// Size of generated normal map.
+const NORMAL_MAP_SIZE: usize = 512;
+
+// Height of bumps relative to their width.
+const BUMPINESS: f32 = 4.0;
+
+#[doc = "Moved out drawing GL stuff to avoid mess with the other code."]
+pub struct GLScene {
+    mesh: MeshBuffers,                 // VAO, VBOs and index buffer.
+    program_id: GLuint,                // Shader program id.
+    diffuse_texture_id: GLuint,        // Diffuse texture id.
+    normal_texture_id: GLuint,         // Normal map id.
+    matrix_uniform_id: GLint,          // MVP uniform location.
+    view_uniform_id: GLint,            // V uniform location.
+    model_uniform_id: GLint,           // M uniform location.
+    light_uniform_id: GLint,           // LightPosition_worldspace uniform location.
+    diffuse_texture_uniform_id: GLint, // DiffuseTextureSampler uniform location.
+    normal_texture_uniform_id: GLint,  // NormalTextureSampler uniform location.
+}
+
+// Normal map of bumpy surface made from noise heights, bumps go along rows
+// and columns like U and V.
+fn normal_map_texture() -> GLuint {
+    let size = NORMAL_MAP_SIZE;
+    let mut heights = vec![0.0; size * size];
+    noise::fill_image_f32(
+        &Fbm::new(Perlin::new(13), 4),
+        &mut heights,
+        size,
+        16.0 / size as f32,
+    );
+    let height = |i: usize, j: usize| heights[(j % size) * size + i % size];
+
+    let mut pixels = Vec::with_capacity(size * size * 3);
+    for j in 0..size {
+        for i in 0..size {
+            // Central differences, wrapped at edges.
+            let dx = height(i + 1, j) - height(i + size - 1, j);
+            let dy = height(i, j + 1) - height(i, j + size - 1);
+            let n = Vector3f(-dx * BUMPINESS, -dy * BUMPINESS, 1.0).normalize();
+
+            // Map -1..1 to 0..255.
+            for &c in [n.0, n.1, n.2].iter() {
+                pixels.push(((c + 1.0) * 127.5).round() as u8);
+            }
+        }
+    }
+
+    let mut texture_id = 0;
+    unsafe {
+        gl::GenTextures(1, &mut texture_id);
+        gl::BindTexture(gl::TEXTURE_2D, texture_id);
+
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::RGB8 as i32,
+            size as i32,
+            size as i32,
+            0,
+            gl::RGB,
+            gl::UNSIGNED_BYTE,
+            pixels.as_ptr() as *const GLvoid,
+        );
+
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_MIN_FILTER,
+            gl::LINEAR_MIPMAP_LINEAR as i32,
+        );
+        gl::GenerateMipmap(gl::TEXTURE_2D);
+    }
+
+    texture_id
+}
+
+impl GLScene {
+    #[doc = "Create scene and init it."]
+    pub fn new(vs: &sdl2::VideoSubsystem) -> GLScene {
+        // Create and compile our GLSL program from the shaders
+        let program_id = glutils::load_program(
+            "data/tut13/NormalMapping.vertexshader",
+            "data/tut13/NormalMapping.fragmentshader",
+        );
+
+        let mut mesh = objloader::obj_load("data/tut08/suzanne.obj").expect("Load obj");
+        mesh.invert_v();
+
+        // Tangents follow final UVs, so they go after V inversion.
+        mesh.generate_tangents();
+        let mesh = mesh.to_indexed(1e-4);
+        println!(
+            "Vertex count: {}, index count: {}",
+            mesh.vertex_count(),
+            mesh.element_count()
+        );
+
+        let (matrix_uniform_id, view_uniform_id, model_uniform_id, light_uniform_id) = unsafe {
+            // Get a handle for our uniforms
+            (
+                gl::GetUniformLocation(program_id, "MVP\x00".as_ptr() as *const i8),
+                gl::GetUniformLocation(program_id, "V\x00".as_ptr() as *const i8),
+                gl::GetUniformLocation(program_id, "M\x00".as_ptr() as *const i8),
+                gl::GetUniformLocation(
+                    program_id,
+                    "LightPosition_worldspace\x00".as_ptr() as *const i8,
+                ),
+            )
+        };
+
+        let (diffuse_texture_uniform_id, normal_texture_uniform_id) = unsafe {
+            (
+                gl::GetUniformLocation(
+                    program_id,
+                    "DiffuseTextureSampler\x00".as_ptr() as *const i8,
+                ),
+                gl::GetUniformLocation(
+                    program_id,
+                    "NormalTextureSampler\x00".as_ptr() as *const i8,
+                ),
+            )
+        };
+
+        // Send vertices, UVs, normals, tangents and indices to buffers.
+        let mesh = mesh.upload();
+
+        let diffuse_texture_id = glutils::load_dds_texture(vs, "data/tut08/uvmap.DDS").unwrap();
+        let normal_texture_id = normal_map_texture();
+
+        GLScene {
+            mesh,
+            program_id,
+            diffuse_texture_id,
+            normal_texture_id,
+            matrix_uniform_id,
+            view_uniform_id,
+            model_uniform_id,
+            light_uniform_id,
+            diffuse_texture_uniform_id,
+            normal_texture_uniform_id,
+        }
+    }
+
+    #[doc = "Update data each frame."]
+    pub fn update(&mut self) {}
+
+    #[doc = "Render scene each frame."]
+    pub fn draw(&self, controls: &Controls) {
+        // Model matrix : an identity matrix (model will be at the origin)
+        let model = Matrix4f::default();
+
+        let mvp = controls.projection.mul(&controls.view).mul(&model);
+
+        unsafe {
+            // Use our shader
+            gl::UseProgram(self.program_id);
+
+            // Send our transformation to the currently bound shader,
+            // in the "MVP" uniform.
+            gl::UniformMatrix4fv(self.matrix_uniform_id, 1, gl::FALSE, mvp.as_ptr());
+            gl::UniformMatrix4fv(self.model_uniform_id, 1, gl::FALSE, model.as_ptr());
+            gl::UniformMatrix4fv(self.view_uniform_id, 1, gl::FALSE, controls.view.as_ptr());
+
+            gl::Uniform3f(self.light_uniform_id, 0.0, 0.0, 4.0);
+
+            // Bind diffuse texture in Texture Unit 0 and normal map in
+            // Texture Unit 1.
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.diffuse_texture_id);
+            gl::Uniform1i(self.diffuse_texture_uniform_id, 0);
+
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.normal_texture_id);
+            gl::Uniform1i(self.normal_texture_uniform_id, 1);
+
+            // Draw the triangles with glDrawElements, index buffer is bound
+            // in vertex array of the mesh.
+            self.mesh.draw();
+        }
+    }
+}
+
+#[doc = "Always clean up after yourself."]
+impl Drop for GLScene {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteProgram(self.program_id);
+
+            gl::DeleteTextures(1, &self.diffuse_texture_id);
+            gl::DeleteTextures(1, &self.normal_texture_id);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+extern crate tutcommon;
+
+use tutcommon::matrix::{ApproxEq, Matrix3f, Matrix4d, Matrix4f, Quaternion, Vector3, Vector3f};
+
+#[test]
+fn scalar_abs_diff() {
+    assert!(1.0f32.abs_diff_eq(&1.05, 0.1));
+    assert!(!1.0f32.abs_diff_eq(&1.2, 0.1));
+    assert!((-1.0f64).abs_diff_eq(&-1.0, 0.0));
+    assert!(!f32::NAN.abs_diff_eq(&f32::NAN, 1.0));
+}
+
+#[test]
+fn scalar_ulps() {
+    let one = 1.0f32;
+    let next = f32::from_bits(one.to_bits() + 1);
+    let far = f32::from_bits(one.to_bits() + 5);
+
+    assert!(one.ulps_eq(&next, 1));
+    assert!(!one.ulps_eq(&next, 0));
+    assert!(one.ulps_eq(&far, 5));
+    assert!(!far.ulps_eq(&one, 4));
+
+    assert!(0.0f32.ulps_eq(&-0.0, 0));
+    assert!(!f32::MIN_POSITIVE.ulps_eq(&-f32::MIN_POSITIVE, u32::MAX));
+    assert!(f64::INFINITY.ulps_eq(&f64::INFINITY, 0));
+    assert!(!f64::NAN.ulps_eq(&f64::NAN, u32::MAX));
+
+    // 0.1 + 0.2 is one ULP away from 0.3.
+    assert!((0.1f64 + 0.2).ulps_eq(&0.3, 1));
+    assert_ne!(0.1f64 + 0.2, 0.3);
+}
+
+#[test]
+fn vectors_and_matrices() {
+    let v = Vector3f(1.0, 2.0, 3.0);
+    assert!(v.abs_diff_eq(&Vector3f(1.0, 2.0, 3.00001), 1e-4));
+    assert!(!v.abs_diff_eq(&Vector3f(1.0, -2.0, 3.0), 1e-4));
+    assert!(Vector3(0.1f64 + 0.2, 0.0, 1.0).ulps_eq(&Vector3(0.3, -0.0, 1.0), 1));
+
+    let m = Matrix4f::rotate(90.0, &Vector3f(0.0, 0.0, 1.0));
+    let expected = Matrix4f::from_columns([
+        [0.0, 1.0, 0.0, 0.0],
+        [-1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+    assert!(m.abs_diff_eq(&expected, 1e-6));
+    // Wrong sign of one entry is detected.
+    assert!(!m.abs_diff_eq(&expected.transpose(), 1e-6));
+
+    let id: Matrix4d = Default::default();
+    assert!(id.ulps_eq(&id.inverse().unwrap(), 0));
+
+    let m3 = Matrix3f::from_columns([[2.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 8.0]]);
+    assert!(m3
+        .mul(&m3.inverse().unwrap())
+        .ulps_eq(&Default::default(), 2));
+
+    let q = Quaternion::from_axis_angle(90.0, &Vector3f(0.0, 1.0, 0.0));
+    assert!(q.abs_diff_eq(&Quaternion::from_euler(0.0, 90.0, 0.0), 1e-6));
+}
@@ -0,0 +1,134 @@
+extern crate tutcommon;
+
+use std::mem;
+
+use tutcommon::color::{
+    linear_to_srgb, srgb_to_linear, Hsl, Hsv, ParseColorError, Rgb, Rgba, Srgb, Srgba,
+};
+
+fn assert_srgb_eq(a: &Srgb, b: &Srgb) {
+    assert!(
+        (a.r - b.r).abs() < 1e-5 && (a.g - b.g).abs() < 1e-5 && (a.b - b.b).abs() < 1e-5,
+        "{:?} != {:?}",
+        a,
+        b
+    );
+}
+
+#[test]
+fn transfer_function() {
+    assert_eq!(srgb_to_linear(0.0), 0.0);
+    assert_eq!(srgb_to_linear(1.0), 1.0);
+    assert_eq!(linear_to_srgb(0.0), 0.0);
+    assert!((linear_to_srgb(1.0) - 1.0).abs() < 1e-6);
+
+    // Middle gray and the linear part near black.
+    assert!((srgb_to_linear(0.5) - 0.214_041_14).abs() < 1e-6);
+    assert!((srgb_to_linear(0.04) - 0.04 / 12.92).abs() < 1e-9);
+    assert!((linear_to_srgb(0.18) - 0.461_356_1).abs() < 1e-6);
+
+    // All 8-bit values survive round trip.
+    for i in 0..=255u8 {
+        let c = Srgb::from_u8(i, i, i);
+        assert_eq!(Srgb::from(Rgb::from(c)).to_u8(), [i, i, i]);
+    }
+}
+
+#[test]
+fn alpha_is_linear() {
+    let c = Srgba::from_u8(255, 128, 0, 128);
+    let l: Rgba = c.into();
+    assert_eq!(l.a, c.a);
+    assert_eq!(l.rgb(), c.rgb().to_linear());
+    assert_eq!(Srgba::from(l).to_u8(), [255, 128, 0, 128]);
+
+    let p = Rgba::new(1.0, 0.5, 0.0, 0.5).premultiply();
+    assert_eq!(p, Rgba::new(0.5, 0.25, 0.0, 0.5));
+}
+
+#[test]
+fn hex_codes() {
+    assert_eq!("#ff8000".parse(), Ok(Srgb::from_u8(255, 128, 0)));
+    assert_eq!(Srgb::from_hex("F80"), Ok(Srgb::from_u8(255, 136, 0)));
+    assert_eq!(
+        Srgba::from_hex("#ff800080"),
+        Ok(Srgba::from_u8(255, 128, 0, 128))
+    );
+    assert_eq!(
+        Srgba::from_hex("#f808"),
+        Ok(Srgba::from_u8(255, 136, 0, 136))
+    );
+    assert_eq!(Srgba::from_hex("000000").unwrap().a, 1.0);
+
+    assert_eq!(Srgb::from_hex("#ff800080"), Err(ParseColorError));
+    assert_eq!(Srgb::from_hex("#ff80"), Err(ParseColorError));
+    assert_eq!(Srgb::from_hex("#ff80g0"), Err(ParseColorError));
+    assert_eq!(Srgb::from_hex(""), Err(ParseColorError));
+    assert_eq!("#12345".parse::<Srgba>(), Err(ParseColorError));
+
+    assert_eq!(Srgb::from_u8(1, 2, 254).to_hex(), "#0102fe");
+    assert_eq!(Srgba::from_hex("#abcdef12").unwrap().to_hex(), "#abcdef12");
+}
+
+#[test]
+fn hsv_and_hsl() {
+    let orange = Srgb::from_u8(255, 128, 0);
+
+    let hsv = Hsv::from(orange);
+    assert!((hsv.h - 30.117_647).abs() < 1e-4);
+    assert_eq!((hsv.s, hsv.v), (1.0, 1.0));
+    assert_srgb_eq(&Srgb::from(hsv), &orange);
+
+    let hsl = Hsl::from(orange);
+    assert!((hsl.h - hsv.h).abs() < 1e-6);
+    assert_eq!((hsl.s, hsl.l), (1.0, 0.5));
+    assert_srgb_eq(&Srgb::from(hsl), &orange);
+
+    let samples = [
+        (0.0, Srgb::new(1.0, 0.0, 0.0)),
+        (120.0, Srgb::new(0.0, 1.0, 0.0)),
+        (240.0, Srgb::new(0.0, 0.0, 1.0)),
+        (300.0, Srgb::new(1.0, 0.0, 1.0)),
+        (-60.0, Srgb::new(1.0, 0.0, 1.0)),
+    ];
+    for &(h, ref c) in samples.iter() {
+        assert_srgb_eq(&Srgb::from(Hsv { h, s: 1.0, v: 1.0 }), c);
+        assert_srgb_eq(&Srgb::from(Hsl { h, s: 1.0, l: 0.5 }), c);
+    }
+
+    // Grays have no hue and saturation.
+    let gray = Srgb::new(0.25, 0.25, 0.25);
+    assert_eq!(
+        Hsv::from(gray),
+        Hsv {
+            h: 0.0,
+            s: 0.0,
+            v: 0.25
+        }
+    );
+    assert_eq!(
+        Hsl::from(gray),
+        Hsl {
+            h: 0.0,
+            s: 0.0,
+            l: 0.25
+        }
+    );
+    assert_srgb_eq(&Srgb::from(Hsl::from(gray)), &gray);
+
+    let teal = Srgb::new(0.2, 0.6, 0.5);
+    assert_srgb_eq(&Srgb::from(Hsv::from(teal)), &teal);
+    assert_srgb_eq(&Srgb::from(Hsl::from(teal)), &teal);
+}
+
+#[test]
+fn layout_for_vertex_attributes() {
+    assert_eq!(mem::size_of::<Rgb>(), 3 * mem::size_of::<f32>());
+    assert_eq!(mem::size_of::<Rgba>(), 4 * mem::size_of::<f32>());
+    assert_eq!(mem::size_of::<Srgb>(), 3 * mem::size_of::<f32>());
+    assert_eq!(mem::size_of::<Srgba>(), 4 * mem::size_of::<f32>());
+
+    let colors = [Rgb::new(0.1, 0.2, 0.3), Rgb::new(0.4, 0.5, 0.6)];
+    let floats = unsafe { std::slice::from_raw_parts(colors.as_ptr() as *const f32, 6) };
+    assert_eq!(floats, &[0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+}
@@ -0,0 +1,199 @@
+extern crate tutcommon;
+
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use tutcommon::curves::{ArcLength, CubicSpline, Curve, Linear};
+use tutcommon::matrix::{ApproxEq, Quaternion, Vector3f};
+
+fn assert_vector_eq(a: &Vector3f, b: &Vector3f) {
+    assert!(a.abs_diff_eq(b, 1e-4), "{:?} != {:?}", a, b);
+}
+
+fn assert_rotation_eq(a: &Quaternion, b: &Quaternion) {
+    assert!((a.dot(b).abs() - 1.0).abs() < 1e-5, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn linear_through_points() {
+    let curve = Linear::new(vec![
+        Vector3f(0.0, 0.0, 0.0),
+        Vector3f(2.0, 0.0, 0.0),
+        Vector3f(2.0, 4.0, 0.0),
+    ])
+    .unwrap();
+
+    assert_vector_eq(&curve.position(0.0), &Vector3f(0.0, 0.0, 0.0));
+    assert_vector_eq(&curve.position(0.25), &Vector3f(1.0, 0.0, 0.0));
+    assert_vector_eq(&curve.position(0.5), &Vector3f(2.0, 0.0, 0.0));
+    assert_vector_eq(&curve.position(0.75), &Vector3f(2.0, 2.0, 0.0));
+    assert_vector_eq(&curve.position(1.0), &Vector3f(2.0, 4.0, 0.0));
+    // Time is clamped.
+    assert_vector_eq(&curve.position(2.0), &Vector3f(2.0, 4.0, 0.0));
+
+    assert_vector_eq(&curve.tangent(0.25), &Vector3f(4.0, 0.0, 0.0));
+    assert_vector_eq(&curve.tangent(0.75), &Vector3f(0.0, 8.0, 0.0));
+
+    assert!(Linear::new(vec![Vector3f(0.0, 0.0, 0.0)]).is_none());
+}
+
+#[test]
+fn bezier_sample_points() {
+    let curve = CubicSpline::bezier(&[
+        Vector3f(0.0, 0.0, 0.0),
+        Vector3f(1.0, 2.0, 0.0),
+        Vector3f(3.0, 2.0, 0.0),
+        Vector3f(4.0, 0.0, 0.0),
+    ])
+    .unwrap();
+
+    assert_vector_eq(&curve.position(0.0), &Vector3f(0.0, 0.0, 0.0));
+    assert_vector_eq(&curve.position(0.5), &Vector3f(2.0, 1.5, 0.0));
+    // (1-t)^3 p0 + 3(1-t)^2 t p1 + 3(1-t) t^2 p2 + t^3 p3
+    assert_vector_eq(&curve.position(0.25), &Vector3f(0.90625, 1.125, 0.0));
+    assert_vector_eq(&curve.position(1.0), &Vector3f(4.0, 0.0, 0.0));
+
+    assert_vector_eq(&curve.tangent(0.0), &Vector3f(3.0, 6.0, 0.0));
+    assert_vector_eq(&curve.tangent(0.5), &Vector3f(4.5, 0.0, 0.0));
+    assert_vector_eq(&curve.tangent(1.0), &Vector3f(3.0, -6.0, 0.0));
+
+    assert!(CubicSpline::bezier(&[Vector3f(0.0, 0.0, 0.0); 5]).is_none());
+    assert_eq!(
+        CubicSpline::bezier(&[Vector3f(0.0, 0.0, 0.0); 7])
+            .unwrap()
+            .segments()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn catmull_rom_passes_through_points() {
+    let points = [
+        Vector3f(0.0, 0.0, 0.0),
+        Vector3f(1.0, 1.0, 0.0),
+        Vector3f(2.0, 0.0, 0.0),
+        Vector3f(3.0, 1.0, 1.0),
+    ];
+    let curve = CubicSpline::catmull_rom(&points).unwrap();
+
+    for (i, p) in points.iter().enumerate() {
+        assert_vector_eq(&curve.position(i as f32 / 3.0), p);
+    }
+
+    // Tangent is (p2 - p0) / 2 per segment, there are 3 segments.
+    assert_vector_eq(&curve.tangent(1.0 / 3.0), &Vector3f(3.0, 0.0, 0.0));
+    assert_vector_eq(&curve.tangent(2.0 / 3.0), &Vector3f(3.0, 0.0, 1.5));
+
+    // Middle of the second segment, by Catmull-Rom matrix.
+    assert_vector_eq(&curve.position(0.5), &Vector3f(1.5, 0.5, -0.0625));
+}
+
+#[test]
+fn hermite_matches_tangents() {
+    let curve = CubicSpline::hermite(
+        &[Vector3f(0.0, 0.0, 0.0), Vector3f(1.0, 0.0, 0.0)],
+        &[Vector3f(0.0, 3.0, 0.0), Vector3f(0.0, -3.0, 0.0)],
+    )
+    .unwrap();
+
+    assert_vector_eq(&curve.tangent(0.0), &Vector3f(0.0, 3.0, 0.0));
+    assert_vector_eq(&curve.tangent(1.0), &Vector3f(0.0, -3.0, 0.0));
+    // h10(0.5) * m0 + h11(0.5) * m1 = 0.125 * 3 + 0.125 * 3
+    assert_vector_eq(&curve.position(0.5), &Vector3f(0.5, 0.75, 0.0));
+
+    assert!(CubicSpline::hermite(&[Vector3f(0.0, 0.0, 0.0); 2], &[]).is_none());
+}
+
+#[test]
+fn arc_length_has_constant_speed() {
+    // Bezier approximation of quarter of unit circle.
+    let k = 0.552_284_8;
+    let arc = CubicSpline::bezier(&[
+        Vector3f(1.0, 0.0, 0.0),
+        Vector3f(1.0, k, 0.0),
+        Vector3f(k, 1.0, 0.0),
+        Vector3f(0.0, 1.0, 0.0),
+    ])
+    .unwrap();
+    let curve = ArcLength::new(arc, 256);
+
+    assert!(
+        (curve.length() - FRAC_PI_2).abs() < 1e-3,
+        "{}",
+        curve.length()
+    );
+    for i in 0..=8 {
+        let t = i as f32 / 8.0;
+        let angle = t * FRAC_PI_2;
+        let p = curve.position(t);
+        assert!(
+            p.abs_diff_eq(&Vector3f(angle.cos(), angle.sin(), 0.0), 1e-3),
+            "{} {:?}",
+            t,
+            p
+        );
+        assert!((curve.tangent(t).length() - curve.length()).abs() < 1e-4);
+    }
+
+    // Line with uneven segments.
+    let line = Linear::new(vec![
+        Vector3f(0.0, 0.0, 0.0),
+        Vector3f(1.0, 0.0, 0.0),
+        Vector3f(4.0, 0.0, 0.0),
+    ])
+    .unwrap();
+    let curve = ArcLength::new(line, 2);
+    assert_eq!(curve.length(), 4.0);
+    assert_vector_eq(&curve.position(0.5), &Vector3f(2.0, 0.0, 0.0));
+    assert_eq!(curve.time_at_distance(1.0), 0.5);
+    assert_eq!(curve.time_at_distance(2.5), 0.75);
+}
+
+#[test]
+fn rotation_curves() {
+    let z = Vector3f(0.0, 0.0, 1.0);
+    let keys = [
+        Quaternion::default(),
+        Quaternion::from_axis_angle(60.0, &z),
+        Quaternion::from_axis_angle(120.0, &z),
+    ];
+    let speed = z * (2.0 * PI / 3.0);
+
+    let linear = Linear::new(keys.to_vec()).unwrap();
+    assert_rotation_eq(
+        &linear.position(0.25),
+        &Quaternion::from_axis_angle(30.0, &z),
+    );
+    assert_vector_eq(&linear.tangent(0.25), &speed);
+
+    let catmull_rom = CubicSpline::catmull_rom(&keys).unwrap();
+    for (i, key) in keys.iter().enumerate() {
+        assert_rotation_eq(&catmull_rom.position(i as f32 / 2.0), key);
+    }
+    assert_vector_eq(&catmull_rom.tangent(0.5), &speed);
+
+    // Rotation with constant speed stays uniform.
+    let hermite = CubicSpline::hermite(&keys, &[z * (PI / 3.0); 3]).unwrap();
+    for i in 0..=8 {
+        let t = i as f32 / 8.0;
+        assert_rotation_eq(
+            &hermite.position(t),
+            &Quaternion::from_axis_angle(120.0 * t, &z),
+        );
+        assert_vector_eq(&hermite.tangent(t), &speed);
+    }
+
+    let x = Vector3f(1.0, 0.0, 0.0);
+    let bezier = CubicSpline::bezier(&[
+        Quaternion::default(),
+        Quaternion::from_axis_angle(30.0, &x),
+        Quaternion::from_axis_angle(60.0, &x),
+        Quaternion::from_axis_angle(90.0, &x),
+    ])
+    .unwrap();
+    assert_rotation_eq(
+        &bezier.position(0.5),
+        &Quaternion::from_axis_angle(45.0, &x),
+    );
+    assert_vector_eq(&bezier.tangent(0.0), &(x * FRAC_PI_2));
+}
@@ -0,0 +1,425 @@
+extern crate tutcommon;
+
+use tutcommon::{
+    geometry::{Aabb, BoundingSphere, Containment, Frustum, Plane, Ray, Triangle},
+    matrix::{self, Matrix4f, Vector3f, Viewport},
+};
+
+const VIEWPORT: Viewport = Viewport {
+    x: 0,
+    y: 0,
+    width: 1024,
+    height: 768,
+};
+
+fn view_projection() -> Matrix4f {
+    Matrix4f::perspective(45.0, 4.0 / 3.0, 0.1, 100.0).mul(&Matrix4f::look_at(
+        &Vector3f(4.0, 3.0, 3.0),
+        &Vector3f(0.0, 0.0, 0.0),
+        &Vector3f(0.0, 1.0, 0.0),
+    ))
+}
+
+#[test]
+fn ray_point_at() {
+    let ray = Ray::new(Vector3f(1.0, 2.0, 3.0), Vector3f(0.0, 0.0, -2.0));
+
+    assert_eq!(ray.direction, Vector3f(0.0, 0.0, -1.0));
+    assert_eq!(ray.point_at(2.0), Vector3f(1.0, 2.0, 1.0));
+}
+
+#[test]
+fn ray_through_window_center_hits_look_at_point() {
+    let ray = Ray::unproject(512.0, 384.0, &view_projection(), &VIEWPORT).unwrap();
+
+    let expected = Vector3f(-4.0, -3.0, -3.0).normalize();
+    assert!(ray.direction.distance(&expected) < 1e-4);
+
+    // Origin is on the near plane.
+    let eye = Vector3f(4.0, 3.0, 3.0);
+    assert!((ray.origin.distance(&eye) - 0.1).abs() < 1e-3);
+
+    // Ray passes through the origin of the world.
+    let t = ray.origin.length();
+    assert!(ray.point_at(t).length() < 1e-3);
+}
+
+#[test]
+fn ray_through_window_corner() {
+    let vp = view_projection();
+    let ray = Ray::unproject(1024.0, 768.0, &vp, &VIEWPORT).unwrap();
+
+    // Any point of the ray is projected back to the corner.
+    let p = vp.transform_point(&ray.point_at(10.0));
+    assert!((p.0 - 1.0).abs() < 1e-3 && (p.1 - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn ray_through_pixel_center() {
+    let vp = view_projection();
+
+    // SDL origin is at upper left corner, OpenGL one is at lower left.
+    for &(x, y, wx, wy) in [
+        (0, 0, 0.5, 767.5),
+        (1023, 767, 1023.5, 0.5),
+        (512, 384, 512.5, 383.5),
+    ]
+    .iter()
+    {
+        let ray = Ray::from_pixel(x, y, &vp, &VIEWPORT).unwrap();
+        let p = matrix::project(&ray.point_at(10.0), &vp, &VIEWPORT).unwrap();
+        assert!(
+            (p.0 - wx).abs() < 1e-2 && (p.1 - wy).abs() < 1e-2,
+            "{:?}",
+            p
+        );
+    }
+
+    // Pixel columns are in window coordinates, not relative to viewport.
+    let viewport = Viewport {
+        x: 24,
+        width: 1000,
+        ..VIEWPORT
+    };
+    let ray = Ray::from_pixel(24, 0, &vp, &viewport).unwrap();
+    let p = matrix::project(&ray.point_at(10.0), &vp, &viewport).unwrap();
+    assert!(
+        (p.0 - 24.5).abs() < 1e-2 && (p.1 - 767.5).abs() < 1e-2,
+        "{:?}",
+        p
+    );
+}
+
+fn unit_box() -> Aabb {
+    Aabb::new(Vector3f(-1.0, -1.0, -1.0), Vector3f(1.0, 1.0, 1.0))
+}
+
+#[test]
+fn ray_triangle() {
+    let triangle = Triangle {
+        a: Vector3f(0.0, 0.0, 0.0),
+        b: Vector3f(1.0, 0.0, 0.0),
+        c: Vector3f(0.0, 1.0, 0.0),
+    };
+
+    let ray = Ray::new(Vector3f(0.25, 0.25, 2.0), Vector3f(0.0, 0.0, -1.0));
+    let hit = ray.intersect_triangle(&triangle).unwrap();
+    assert!((hit.distance - 2.0).abs() < 1e-6);
+    assert_eq!(hit.normal, Vector3f(0.0, 0.0, 1.0));
+    assert_eq!(triangle.normal(), Vector3f(0.0, 0.0, 1.0));
+
+    // Back side is hit too.
+    let back = Ray::new(Vector3f(0.25, 0.25, -3.0), Vector3f(0.0, 0.0, 1.0));
+    assert!((back.intersect_triangle(&triangle).unwrap().distance - 3.0).abs() < 1e-6);
+
+    // Outside of triangle.
+    let miss = Ray::new(Vector3f(0.75, 0.75, 2.0), Vector3f(0.0, 0.0, -1.0));
+    assert!(miss.intersect_triangle(&triangle).is_none());
+
+    // Behind the ray.
+    let behind = Ray::new(Vector3f(0.25, 0.25, 2.0), Vector3f(0.0, 0.0, 1.0));
+    assert!(behind.intersect_triangle(&triangle).is_none());
+
+    // Parallel.
+    let parallel = Ray::new(Vector3f(-1.0, 0.25, 0.0), Vector3f(1.0, 0.0, 0.0));
+    assert!(parallel.intersect_triangle(&triangle).is_none());
+}
+
+#[test]
+fn ray_triangle_list_returns_nearest() {
+    let vertices = [
+        Vector3f(-1.0, -1.0, -5.0),
+        Vector3f(1.0, -1.0, -5.0),
+        Vector3f(0.0, 1.0, -5.0),
+        Vector3f(-1.0, -1.0, -2.0),
+        Vector3f(1.0, -1.0, -2.0),
+        Vector3f(0.0, 1.0, -2.0),
+    ];
+    let ray = Ray::new(Vector3f(0.0, 0.0, 0.0), Vector3f(0.0, 0.0, -1.0));
+
+    let hit = ray.intersect_triangles(&vertices).unwrap();
+    assert!((hit.distance - 2.0).abs() < 1e-6);
+
+    let miss = Ray::new(Vector3f(5.0, 0.0, 0.0), Vector3f(0.0, 0.0, -1.0));
+    assert!(miss.intersect_triangles(&vertices).is_none());
+}
+
+#[test]
+fn ray_aabb() {
+    let aabb = unit_box();
+
+    let ray = Ray::new(Vector3f(-5.0, 0.5, 0.0), Vector3f(1.0, 0.0, 0.0));
+    let hit = ray.intersect_aabb(&aabb).unwrap();
+    assert!((hit.distance - 4.0).abs() < 1e-6);
+    assert_eq!(hit.normal, Vector3f(-1.0, 0.0, 0.0));
+
+    let diagonal = Ray::new(Vector3f(3.0, 3.0, 3.0), Vector3f(-1.0, -1.0, -1.0));
+    let hit = diagonal.intersect_aabb(&aabb).unwrap();
+    assert!((hit.distance - 2.0 * 3f32.sqrt()).abs() < 1e-5);
+
+    let top = Ray::new(Vector3f(0.2, 5.0, 0.3), Vector3f(0.0, -1.0, 0.0));
+    let hit = top.intersect_aabb(&aabb).unwrap();
+    assert!((hit.distance - 4.0).abs() < 1e-6);
+    assert_eq!(hit.normal, Vector3f(0.0, 1.0, 0.0));
+
+    // Starting inside gives exit point.
+    let inside = Ray::new(Vector3f(0.0, 0.0, 0.0), Vector3f(0.0, 0.0, 1.0));
+    let hit = inside.intersect_aabb(&aabb).unwrap();
+    assert!((hit.distance - 1.0).abs() < 1e-6);
+    assert_eq!(hit.normal, Vector3f(0.0, 0.0, 1.0));
+
+    let miss = Ray::new(Vector3f(-5.0, 2.0, 0.0), Vector3f(1.0, 0.0, 0.0));
+    assert!(miss.intersect_aabb(&aabb).is_none());
+    let away = Ray::new(Vector3f(-5.0, 0.0, 0.0), Vector3f(-1.0, 0.0, 0.0));
+    assert!(away.intersect_aabb(&aabb).is_none());
+}
+
+#[test]
+fn ray_obb() {
+    let aabb = unit_box();
+    let model = Matrix4f::translate(&Vector3f(10.0, 0.0, 0.0))
+        .mul(&Matrix4f::rotate(45.0, &Vector3f(0.0, 1.0, 0.0)))
+        .mul(&Matrix4f::scale(&Vector3f(2.0, 2.0, 2.0)));
+
+    // Rotated box is hit by its edge at distance sqrt(2) * 2 from center.
+    let ray = Ray::new(Vector3f(0.0, 0.0, 0.0), Vector3f(1.0, 0.0, 0.0));
+    let hit = ray.intersect_obb(&aabb, &model).unwrap();
+    assert!((hit.distance - (10.0 - 2.0 * 2f32.sqrt())).abs() < 1e-4);
+    assert!((hit.normal.length() - 1.0).abs() < 1e-5);
+    assert!(hit.normal.0 < 0.0);
+
+    // The same ray misses the box without translation of model.
+    let miss = Ray::new(Vector3f(0.0, 5.0, 0.0), Vector3f(1.0, 0.0, 0.0));
+    assert!(miss.intersect_obb(&aabb, &model).is_none());
+
+    // Unrotated box gives the same result as AABB test.
+    let shifted = Matrix4f::translate(&Vector3f(0.0, 0.0, -5.0));
+    let ray = Ray::new(Vector3f(0.0, 0.0, 0.0), Vector3f(0.0, 0.0, -1.0));
+    let hit = ray.intersect_obb(&aabb, &shifted).unwrap();
+    assert!((hit.distance - 4.0).abs() < 1e-5);
+    assert!(hit.normal.distance(&Vector3f(0.0, 0.0, 1.0)) < 1e-5);
+}
+
+#[test]
+fn ray_sphere_and_plane() {
+    let sphere = BoundingSphere {
+        center: Vector3f(0.0, 0.0, -10.0),
+        radius: 2.0,
+    };
+    let ray = Ray::new(Vector3f(0.0, 0.0, 0.0), Vector3f(0.0, 0.0, -1.0));
+    let hit = ray.intersect_sphere(&sphere).unwrap();
+    assert!((hit.distance - 8.0).abs() < 1e-5);
+    assert!(hit.normal.distance(&Vector3f(0.0, 0.0, 1.0)) < 1e-5);
+
+    let miss = Ray::new(Vector3f(0.0, 3.0, 0.0), Vector3f(0.0, 0.0, -1.0));
+    assert!(miss.intersect_sphere(&sphere).is_none());
+
+    let plane = Plane::from_point_normal(&Vector3f(0.0, -1.0, 0.0), &Vector3f(0.0, 2.0, 0.0));
+    assert_eq!(plane.signed_distance(&Vector3f(5.0, 1.0, 5.0)), 2.0);
+    let down = Ray::new(Vector3f(0.0, 3.0, 0.0), Vector3f(0.0, -1.0, 0.0));
+    let hit = down.intersect_plane(&plane).unwrap();
+    assert!((hit.distance - 4.0).abs() < 1e-6);
+    assert!(ray.intersect_plane(&plane).is_none());
+    assert!(Ray::new(Vector3f(0.0, 3.0, 0.0), Vector3f(0.0, 1.0, 0.0))
+        .intersect_plane(&plane)
+        .is_none());
+
+    let triangle = Triangle {
+        a: Vector3f(0.0, 0.0, 0.0),
+        b: Vector3f(0.0, 0.0, 1.0),
+        c: Vector3f(1.0, 0.0, 0.0),
+    };
+    assert_eq!(triangle.plane().normal, Vector3f(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn sphere_sphere() {
+    let a = BoundingSphere {
+        center: Vector3f(0.0, 0.0, 0.0),
+        radius: 1.0,
+    };
+    let b = BoundingSphere {
+        center: Vector3f(1.5, 0.0, 0.0),
+        radius: 1.0,
+    };
+
+    let hit = a.intersect_sphere(&b).unwrap();
+    assert!((hit.distance - 0.5).abs() < 1e-6);
+    assert_eq!(hit.normal, Vector3f(1.0, 0.0, 0.0));
+    assert_eq!(
+        b.intersect_sphere(&a).unwrap().normal,
+        Vector3f(-1.0, 0.0, 0.0)
+    );
+
+    let far = BoundingSphere {
+        center: Vector3f(0.0, 3.0, 0.0),
+        radius: 1.0,
+    };
+    assert!(a.intersect_sphere(&far).is_none());
+}
+
+#[test]
+fn aabb_aabb() {
+    let a = unit_box();
+    let b = Aabb::new(Vector3f(0.5, -0.5, -0.5), Vector3f(3.0, 0.5, 0.5));
+
+    let hit = a.intersect_aabb(&b).unwrap();
+    assert!((hit.distance - 0.5).abs() < 1e-6);
+    assert_eq!(hit.normal, Vector3f(1.0, 0.0, 0.0));
+    assert_eq!(
+        b.intersect_aabb(&a).unwrap().normal,
+        Vector3f(-1.0, 0.0, 0.0)
+    );
+
+    let c = Aabb::new(Vector3f(2.0, 2.0, 2.0), Vector3f(3.0, 3.0, 3.0));
+    assert!(a.intersect_aabb(&c).is_none());
+}
+
+#[test]
+fn bounding_volumes_from_points() {
+    let points = [
+        Vector3f(1.0, 2.0, 3.0),
+        Vector3f(-1.0, 0.0, 5.0),
+        Vector3f(0.0, -2.0, 4.0),
+    ];
+
+    let aabb = Aabb::from_points(&points).unwrap();
+    assert_eq!(aabb.min, Vector3f(-1.0, -2.0, 3.0));
+    assert_eq!(aabb.max, Vector3f(1.0, 2.0, 5.0));
+    assert_eq!(aabb.center(), Vector3f(0.0, 0.0, 4.0));
+    assert_eq!(aabb.size(), Vector3f(2.0, 4.0, 2.0));
+    assert!(points.iter().all(|p| aabb.contains_point(p)));
+    assert!(Aabb::from_points(&[]).is_none());
+
+    let sphere = BoundingSphere::from_points(&points).unwrap();
+    assert!(points.iter().all(|p| sphere.contains_point(p)));
+
+    let moved = aabb.transform(&Matrix4f::rotate(90.0, &Vector3f(0.0, 0.0, 1.0)));
+    assert!(moved.min.distance(&Vector3f(-2.0, -1.0, 3.0)) < 1e-5);
+    assert!(moved.max.distance(&Vector3f(2.0, 1.0, 5.0)) < 1e-5);
+}
+
+#[test]
+fn perspective_frustum() {
+    // Camera at origin looks along -Z, 90 degrees field of view.
+    let frustum = Frustum::from_matrix(&Matrix4f::perspective(90.0, 1.0, 1.0, 10.0));
+
+    assert_eq!(
+        frustum.contains_point(&Vector3f(0.0, 0.0, -5.0)),
+        Containment::Inside
+    );
+    assert_eq!(
+        frustum.contains_point(&Vector3f(0.0, 0.0, -0.5)),
+        Containment::Outside
+    );
+    assert_eq!(
+        frustum.contains_point(&Vector3f(0.0, 0.0, -11.0)),
+        Containment::Outside
+    );
+    assert_eq!(
+        frustum.contains_point(&Vector3f(4.0, 0.0, -5.0)),
+        Containment::Inside
+    );
+    assert_eq!(
+        frustum.contains_point(&Vector3f(6.0, 0.0, -5.0)),
+        Containment::Outside
+    );
+    assert_eq!(
+        frustum.contains_point(&Vector3f(0.0, 0.0, 5.0)),
+        Containment::Outside
+    );
+
+    let inside = Aabb::new(Vector3f(-1.0, -1.0, -6.0), Vector3f(1.0, 1.0, -4.0));
+    assert_eq!(frustum.intersects_aabb(&inside), Containment::Inside);
+    let crossing_far = Aabb::new(Vector3f(-1.0, -1.0, -12.0), Vector3f(1.0, 1.0, -8.0));
+    assert_eq!(
+        frustum.intersects_aabb(&crossing_far),
+        Containment::Intersecting
+    );
+    let crossing_side = Aabb::new(Vector3f(4.0, -1.0, -6.0), Vector3f(6.0, 1.0, -4.0));
+    assert_eq!(
+        frustum.intersects_aabb(&crossing_side),
+        Containment::Intersecting
+    );
+    let behind = Aabb::new(Vector3f(-1.0, -1.0, 1.0), Vector3f(1.0, 1.0, 3.0));
+    assert_eq!(frustum.intersects_aabb(&behind), Containment::Outside);
+
+    let sphere = |x: f32, z: f32, radius: f32| BoundingSphere {
+        center: Vector3f(x, 0.0, z),
+        radius,
+    };
+    assert_eq!(
+        frustum.intersects_sphere(&sphere(0.0, -5.0, 1.0)),
+        Containment::Inside
+    );
+    assert_eq!(
+        frustum.intersects_sphere(&sphere(0.0, -0.5, 1.0)),
+        Containment::Intersecting
+    );
+    assert_eq!(
+        frustum.intersects_sphere(&sphere(-8.0, -5.0, 1.0)),
+        Containment::Outside
+    );
+    // Right plane is x = -z, its distance to the center is (10 - 5) / sqrt(2).
+    assert_eq!(
+        frustum.intersects_sphere(&sphere(10.0, -5.0, 3.6)),
+        Containment::Intersecting
+    );
+    assert_eq!(
+        frustum.intersects_sphere(&sphere(10.0, -5.0, 3.5)),
+        Containment::Outside
+    );
+}
+
+#[test]
+fn ortho_frustum() {
+    let frustum = Frustum::from_matrix(&Matrix4f::ortho(-10.0, 10.0, -5.0, 5.0, 0.0, 100.0));
+
+    assert_eq!(
+        frustum.contains_point(&Vector3f(9.0, 4.0, -50.0)),
+        Containment::Inside
+    );
+    assert_eq!(
+        frustum.contains_point(&Vector3f(9.0, 6.0, -50.0)),
+        Containment::Outside
+    );
+    assert_eq!(
+        frustum.contains_point(&Vector3f(0.0, 0.0, -101.0)),
+        Containment::Outside
+    );
+    assert_eq!(
+        frustum.contains_point(&Vector3f(0.0, 0.0, 0.0)),
+        Containment::Intersecting
+    );
+
+    let aabb = Aabb::new(Vector3f(8.0, -1.0, -10.0), Vector3f(12.0, 1.0, -5.0));
+    assert_eq!(frustum.intersects_aabb(&aabb), Containment::Intersecting);
+    let aabb = Aabb::new(Vector3f(11.0, -1.0, -10.0), Vector3f(12.0, 1.0, -5.0));
+    assert_eq!(frustum.intersects_aabb(&aabb), Containment::Outside);
+
+    let sphere = BoundingSphere {
+        center: Vector3f(0.0, 0.0, -50.0),
+        radius: 4.0,
+    };
+    assert_eq!(frustum.intersects_sphere(&sphere), Containment::Inside);
+}
+
+#[test]
+fn frustum_in_world_space() {
+    let frustum = Frustum::from_matrix(&view_projection());
+
+    // Camera at (4, 3, 3) looks at the origin.
+    assert_eq!(
+        frustum.contains_point(&Vector3f(0.0, 0.0, 0.0)),
+        Containment::Inside
+    );
+    assert_eq!(
+        frustum.contains_point(&Vector3f(8.0, 6.0, 6.0)),
+        Containment::Outside
+    );
+    assert_eq!(frustum.intersects_aabb(&unit_box()), Containment::Inside);
+
+    for plane in frustum.planes.iter() {
+        assert!((plane.normal.length() - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,239 @@
+#![cfg(any(
+    feature = "mint",
+    feature = "glam",
+    feature = "nalgebra",
+    feature = "cgmath"
+))]
+
+#[cfg(feature = "cgmath")]
+extern crate cgmath;
+#[cfg(feature = "glam")]
+extern crate glam;
+#[cfg(feature = "mint")]
+extern crate mint;
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
+extern crate tutcommon;
+
+use std::fmt::Debug;
+
+use tutcommon::matrix::{
+    Matrix4d, Matrix4f, Quaternion, Vector2, Vector2f, Vector3, Vector3f, Vector4, Vector4f,
+};
+
+// Every element differs, so swapped rows and columns are detected.
+fn sample_matrix() -> Matrix4f {
+    Matrix4f::from_columns([
+        [1.0, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 8.0],
+        [9.0, 10.0, 11.0, 12.0],
+        [13.0, 14.0, 15.0, 16.0],
+    ])
+}
+
+fn sample_quaternion() -> Quaternion<f32> {
+    Quaternion::from_euler(10.0, 20.0, 30.0)
+}
+
+const V2: Vector2f = Vector2(1.0, 2.0);
+const V3: Vector3f = Vector3(1.0, 2.0, 3.0);
+const V4: Vector4f = Vector4(1.0, 2.0, 3.0, 4.0);
+// Needs double precision.
+const D3: Vector3<f64> = Vector3(1.0e7, 2.0, 3.0);
+// Vector multiplied by sample matrix.
+#[cfg(any(feature = "glam", feature = "nalgebra", feature = "cgmath"))]
+const COLUMN: Vector4f = Vector4(1.0, -1.0, 0.5, 2.0);
+
+// Convert value to type `U` and back.
+fn round_trip<T, U>(value: &T) -> U
+where
+    T: Clone + Debug + PartialEq + From<U> + Into<U>,
+    U: Clone,
+{
+    let other: U = value.clone().into();
+    assert_eq!(T::from(other.clone()), *value);
+    other
+}
+
+// Vector rotated by the other library matches sample quaternion. Mint has
+// no arithmetic, so these checks are for the other libraries.
+#[cfg(any(feature = "glam", feature = "nalgebra", feature = "cgmath"))]
+fn assert_rotates_like_sample(rotated: Vector3f) {
+    assert!(rotated.distance(&sample_quaternion().rotate_vector(&V3)) < 1e-5);
+}
+
+// Matrices of both libraries multiply column vector the same way.
+#[cfg(any(feature = "glam", feature = "nalgebra", feature = "cgmath"))]
+fn assert_multiplies_like_sample(product: Vector4f) {
+    assert_eq!(product, &sample_matrix() * COLUMN);
+}
+
+#[cfg(feature = "mint")]
+mod with_mint {
+    use super::*;
+    use tutcommon::matrix::Matrix3d;
+
+    #[test]
+    fn vectors() {
+        let m2: mint::Vector2<f32> = round_trip(&V2);
+        assert_eq!((m2.x, m2.y), (1.0, 2.0));
+        let m3: mint::Vector3<f32> = round_trip(&V3);
+        assert_eq!((m3.x, m3.y, m3.z), (1.0, 2.0, 3.0));
+        let m4: mint::Vector4<f32> = round_trip(&V4);
+        assert_eq!((m4.x, m4.y, m4.z, m4.w), (1.0, 2.0, 3.0, 4.0));
+        let _: mint::Vector3<f64> = round_trip(&D3);
+    }
+
+    #[test]
+    fn matrices() {
+        let mm: mint::ColumnMatrix4<f32> = round_trip(&sample_matrix());
+        assert_eq!((mm.x.x, mm.x.y, mm.x.w), (1.0, 2.0, 4.0));
+        assert_eq!((mm.w.x, mm.w.y, mm.w.z), (13.0, 14.0, 15.0));
+
+        let m3 = Matrix3d::from_columns([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        let mm3: mint::ColumnMatrix3<f64> = round_trip(&m3);
+        assert_eq!((mm3.y.x, mm3.z.y), (4.0, 8.0));
+        let _: mint::ColumnMatrix3<f32> = round_trip(&sample_matrix().to_matrix3());
+        let _: mint::ColumnMatrix4<f64> = round_trip(&Matrix4d::from(sample_matrix()));
+    }
+
+    #[test]
+    fn quaternions() {
+        let q = sample_quaternion();
+        let mq: mint::Quaternion<f32> = round_trip(&q);
+        assert_eq!((mq.v.x, mq.v.y, mq.v.z, mq.s), (q.x, q.y, q.z, q.w));
+    }
+}
+
+#[cfg(feature = "glam")]
+mod with_glam {
+    use super::*;
+
+    #[test]
+    fn vectors() {
+        assert_eq!(round_trip::<_, glam::Vec2>(&V2), glam::Vec2::new(1.0, 2.0));
+        assert_eq!(
+            round_trip::<_, glam::Vec3>(&V3),
+            glam::Vec3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            round_trip::<_, glam::Vec4>(&V4),
+            glam::Vec4::new(1.0, 2.0, 3.0, 4.0)
+        );
+        assert_eq!(
+            round_trip::<_, glam::DVec3>(&D3),
+            glam::DVec3::new(1.0e7, 2.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn matrices() {
+        let gm: glam::Mat4 = round_trip(&sample_matrix());
+        assert_eq!(gm.col(0), glam::Vec4::new(1.0, 2.0, 3.0, 4.0));
+        assert_eq!(gm.col(3), glam::Vec4::new(13.0, 14.0, 15.0, 16.0));
+        assert_multiplies_like_sample(Vector4f::from(gm * glam::Vec4::from(COLUMN)));
+
+        let gm3: glam::Mat3 = round_trip(&sample_matrix().to_matrix3());
+        assert_eq!(gm3.col(1), glam::Vec3::new(5.0, 6.0, 7.0));
+        let _: glam::DMat4 = round_trip(&Matrix4d::from(sample_matrix()));
+    }
+
+    #[test]
+    fn quaternions() {
+        let q = sample_quaternion();
+        let gq: glam::Quat = round_trip(&q);
+        assert_eq!(gq.to_array(), [q.x, q.y, q.z, q.w]);
+        assert_rotates_like_sample(Vector3f::from(gq * glam::Vec3::from(V3)));
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+mod with_nalgebra {
+    use super::*;
+
+    #[test]
+    fn vectors() {
+        assert_eq!(
+            round_trip::<_, nalgebra::Vector2<f32>>(&V2),
+            nalgebra::Vector2::new(1.0, 2.0)
+        );
+        assert_eq!(
+            round_trip::<_, nalgebra::Vector3<f32>>(&V3),
+            nalgebra::Vector3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            round_trip::<_, nalgebra::Vector4<f32>>(&V4),
+            nalgebra::Vector4::new(1.0, 2.0, 3.0, 4.0)
+        );
+        let _: nalgebra::Vector3<f64> = round_trip(&D3);
+    }
+
+    #[test]
+    fn matrices() {
+        let nm: nalgebra::Matrix4<f32> = round_trip(&sample_matrix());
+        // nalgebra stores matrices column major too.
+        assert_eq!(&nm.as_slice()[..4], &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(nm[(0, 3)], 13.0);
+        assert_eq!(nm[(3, 0)], 4.0);
+        assert_multiplies_like_sample(Vector4f::from(nm * nalgebra::Vector4::from(COLUMN)));
+
+        let nm3: nalgebra::Matrix3<f32> = round_trip(&sample_matrix().to_matrix3());
+        assert_eq!(nm3[(0, 1)], 5.0);
+        let _: nalgebra::Matrix4<f64> = round_trip(&Matrix4d::from(sample_matrix()));
+    }
+
+    #[test]
+    fn quaternions() {
+        let q = sample_quaternion();
+        let nq: nalgebra::Quaternion<f32> = round_trip(&q);
+        assert_eq!(nq.coords, nalgebra::Vector4::new(q.x, q.y, q.z, q.w));
+
+        // Unit quaternion is normalized, so it's compared with tolerance.
+        let uq: nalgebra::UnitQuaternion<f32> = q.into();
+        assert_rotates_like_sample(Vector3f::from(uq * nalgebra::Vector3::from(V3)));
+        assert!(Quaternion::from(uq).dot(&q) > 1.0 - 1e-6);
+    }
+}
+
+#[cfg(feature = "cgmath")]
+mod with_cgmath {
+    use super::*;
+    use cgmath::Rotation;
+
+    #[test]
+    fn vectors() {
+        assert_eq!(
+            round_trip::<_, cgmath::Vector2<f32>>(&V2),
+            cgmath::Vector2::new(1.0, 2.0)
+        );
+        assert_eq!(
+            round_trip::<_, cgmath::Vector3<f32>>(&V3),
+            cgmath::Vector3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            round_trip::<_, cgmath::Vector4<f32>>(&V4),
+            cgmath::Vector4::new(1.0, 2.0, 3.0, 4.0)
+        );
+        let _: cgmath::Vector3<f64> = round_trip(&D3);
+    }
+
+    #[test]
+    fn matrices() {
+        let cm: cgmath::Matrix4<f32> = round_trip(&sample_matrix());
+        assert_eq!(cm.x, cgmath::Vector4::new(1.0, 2.0, 3.0, 4.0));
+        assert_eq!(cm.w, cgmath::Vector4::new(13.0, 14.0, 15.0, 16.0));
+        assert_multiplies_like_sample(Vector4f::from(cm * cgmath::Vector4::from(COLUMN)));
+
+        let cm3: cgmath::Matrix3<f32> = round_trip(&sample_matrix().to_matrix3());
+        assert_eq!(cm3.y, cgmath::Vector3::new(5.0, 6.0, 7.0));
+        let _: cgmath::Matrix4<f64> = round_trip(&Matrix4d::from(sample_matrix()));
+    }
+
+    #[test]
+    fn quaternions() {
+        let q = sample_quaternion();
+        let cq: cgmath::Quaternion<f32> = round_trip(&q);
+        assert_eq!((cq.v.x, cq.v.y, cq.v.z, cq.s), (q.x, q.y, q.z, q.w));
+        assert_rotates_like_sample(Vector3f::from(cq.rotate_vector(cgmath::Vector3::from(V3))));
+    }
+}
@@ -0,0 +1,422 @@
+extern crate tutcommon;
+
+use tutcommon::matrix::{self, Deg, Matrix3f, Matrix4f, Rad, Vector3f, Vector4f, Viewport};
+
+fn assert_matrix_eq(a: &Matrix4f, b: &Matrix4f) {
+    for (col_a, col_b) in a.columns().iter().zip(b.columns().iter()) {
+        for (x, y) in col_a.iter().zip(col_b.iter()) {
+            assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+}
+
+fn assert_matrix3_eq(a: &Matrix3f, b: &Matrix3f) {
+    for (col_a, col_b) in a.columns().iter().zip(b.columns().iter()) {
+        for (x, y) in col_a.iter().zip(col_b.iter()) {
+            assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn transpose_swaps_rows_and_columns() {
+    let m = Matrix4f::from_columns([
+        [1.0, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 8.0],
+        [9.0, 10.0, 11.0, 12.0],
+        [13.0, 14.0, 15.0, 16.0],
+    ]);
+    let t = Matrix4f::from_columns([
+        [1.0, 5.0, 9.0, 13.0],
+        [2.0, 6.0, 10.0, 14.0],
+        [3.0, 7.0, 11.0, 15.0],
+        [4.0, 8.0, 12.0, 16.0],
+    ]);
+
+    assert_eq!(m.transpose(), t);
+    assert_eq!(m.transpose().transpose(), m);
+}
+
+#[test]
+fn determinant_of_known_matrices() {
+    assert_eq!(Matrix4f::default().determinant(), 1.0);
+    assert_eq!(
+        Matrix4f::scale(&Vector3f(2.0, 3.0, 4.0)).determinant(),
+        24.0
+    );
+    assert_eq!(
+        Matrix4f::translate(&Vector3f(2.0, 3.0, 4.0)).determinant(),
+        1.0
+    );
+
+    let m = Matrix4f::from_columns([
+        [1.0, 0.0, 2.0, -1.0],
+        [3.0, 0.0, 0.0, 5.0],
+        [2.0, 1.0, 4.0, -3.0],
+        [1.0, 0.0, 5.0, 0.0],
+    ]);
+    assert_eq!(m.determinant(), 30.0);
+    assert_eq!(m.transpose().determinant(), 30.0);
+
+    let r = Matrix4f::rotate(30.0, &Vector3f(1.0, 2.0, 3.0));
+    assert!((r.determinant() - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn inverse_of_known_matrices() {
+    let t = Matrix4f::translate(&Vector3f(1.0, -2.0, 3.0));
+    assert_matrix_eq(
+        &t.inverse().unwrap(),
+        &Matrix4f::translate(&Vector3f(-1.0, 2.0, -3.0)),
+    );
+
+    let s = Matrix4f::scale(&Vector3f(2.0, 4.0, 0.5));
+    assert_matrix_eq(
+        &s.inverse().unwrap(),
+        &Matrix4f::scale(&Vector3f(0.5, 0.25, 2.0)),
+    );
+
+    let r = Matrix4f::rotate(75.0, &Vector3f(0.0, 1.0, 1.0));
+    assert_matrix_eq(&r.inverse().unwrap(), &r.transpose());
+    assert_matrix_eq(
+        &r.inverse().unwrap(),
+        &Matrix4f::rotate(-75.0, &Vector3f(0.0, 1.0, 1.0)),
+    );
+
+    let m = Matrix4f::from_columns([
+        [1.0, 0.0, 2.0, -1.0],
+        [3.0, 0.0, 0.0, 5.0],
+        [2.0, 1.0, 4.0, -3.0],
+        [1.0, 0.0, 5.0, 0.0],
+    ]);
+    let inv = m.inverse().unwrap();
+    assert_matrix_eq(&m.mul(&inv), &Matrix4f::default());
+    assert_matrix_eq(&inv.mul(&m), &Matrix4f::default());
+    assert_matrix_eq(&inv.inverse().unwrap(), &m);
+}
+
+#[test]
+fn inverse_of_view_gives_camera_position() {
+    let view = Matrix4f::look_at(
+        &Vector3f(4.0, 3.0, 3.0),
+        &Vector3f(0.0, 0.0, 0.0),
+        &Vector3f(0.0, 1.0, 0.0),
+    );
+    let camera = view.inverse().unwrap();
+    let position = camera.columns()[3];
+
+    assert!((position[0] - 4.0).abs() < 1e-5);
+    assert!((position[1] - 3.0).abs() < 1e-5);
+    assert!((position[2] - 3.0).abs() < 1e-5);
+    assert!((position[3] - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn inverse_of_singular_matrix_is_none() {
+    assert!(Matrix4f::scale(&Vector3f(1.0, 0.0, 1.0))
+        .inverse()
+        .is_none());
+
+    let m = Matrix4f::from_columns([
+        [1.0, 2.0, 3.0, 4.0],
+        [2.0, 4.0, 6.0, 8.0],
+        [0.0, 1.0, 0.0, 1.0],
+        [1.0, 0.0, 1.0, 0.0],
+    ]);
+    assert_eq!(m.determinant(), 0.0);
+    assert!(m.inverse().is_none());
+}
+
+#[test]
+fn matrix3_inverse() {
+    let m = Matrix3f::from_columns([[2.0, 0.0, 1.0], [1.0, 3.0, 0.0], [0.0, 1.0, 4.0]]);
+    assert_eq!(m.determinant(), 25.0);
+
+    let inv = m.inverse().unwrap();
+    assert_matrix3_eq(&m.mul(&inv), &Matrix3f::default());
+    assert_matrix3_eq(&inv.mul(&m), &Matrix3f::default());
+
+    let singular = Matrix3f::from_columns([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 1.0, 0.0]]);
+    assert!(singular.inverse().is_none());
+}
+
+#[test]
+fn normal_matrix_of_rotation_is_rotation() {
+    let model = Matrix4f::translate(&Vector3f(1.0, 2.0, 3.0))
+        .mul(&Matrix4f::rotate(60.0, &Vector3f(1.0, 1.0, 0.0)));
+
+    assert_matrix3_eq(
+        &model.normal_matrix().unwrap(),
+        &Matrix4f::rotate(60.0, &Vector3f(1.0, 1.0, 0.0)).to_matrix3(),
+    );
+}
+
+#[test]
+fn normal_matrix_keeps_normals_perpendicular() {
+    let model = Matrix4f::rotate(30.0, &Vector3f(0.0, 0.0, 1.0))
+        .mul(&Matrix4f::scale(&Vector3f(4.0, 1.0, 0.5)));
+    let normal_matrix = model.normal_matrix().unwrap();
+
+    assert_matrix3_eq(
+        &Matrix4f::scale(&Vector3f(2.0, 1.0, 1.0))
+            .normal_matrix()
+            .unwrap(),
+        &Matrix3f::from_columns([[0.5, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]),
+    );
+
+    // Surface x + y + z = 0.
+    let normal = Vector3f(1.0, 1.0, 1.0);
+    let tangent = Vector3f(1.0, -1.0, 0.0);
+
+    let n = &normal_matrix * normal;
+    let t = &model.to_matrix3() * tangent;
+    assert!(n.dot(&t).abs() < 1e-5);
+
+    // Transforming with the model matrix itself breaks perpendicularity.
+    let wrong = &model.to_matrix3() * normal;
+    assert!(wrong.dot(&t).abs() > 1e-1);
+
+    assert!(Matrix4f::scale(&Vector3f(1.0, 1.0, 0.0))
+        .normal_matrix()
+        .is_none());
+}
+
+#[test]
+fn matrix_vector_multiplication() {
+    let m = Matrix4f::from_columns([
+        [1.0, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 8.0],
+        [9.0, 10.0, 11.0, 12.0],
+        [13.0, 14.0, 15.0, 16.0],
+    ]);
+
+    assert_eq!(
+        &m * Vector4f(1.0, 0.0, 0.0, 0.0),
+        Vector4f(1.0, 2.0, 3.0, 4.0)
+    );
+    assert_eq!(
+        &m * Vector4f(1.0, 1.0, 0.0, 2.0),
+        Vector4f(32.0, 36.0, 40.0, 44.0)
+    );
+
+    let m3 = m.to_matrix3();
+    assert_eq!(&m3 * Vector3f(0.0, 1.0, 1.0), Vector3f(14.0, 16.0, 18.0));
+}
+
+#[test]
+fn products_are_summed_from_positive_zero() {
+    // Sum of negative zeros is positive zero with and without `simd` feature.
+    let v = &Matrix4f::default() * Vector4f(-0.0, -0.0, -0.0, -0.0);
+    for &x in [v.0, v.1, v.2, v.3].iter() {
+        assert!(x == 0.0 && x.is_sign_positive(), "{:?}", v);
+    }
+}
+
+#[test]
+fn transform_point_and_direction() {
+    let model = Matrix4f::translate(&Vector3f(1.0, 2.0, 3.0))
+        .mul(&Matrix4f::rotate(90.0, &Vector3f(0.0, 0.0, 1.0)))
+        .mul(&Matrix4f::scale(&Vector3f(2.0, 2.0, 2.0)));
+
+    let p = model.transform_point(&Vector3f(1.0, 0.0, 0.0));
+    assert!(p.distance(&Vector3f(1.0, 4.0, 3.0)) < 1e-5);
+
+    let d = model.transform_direction(&Vector3f(1.0, 0.0, 0.0));
+    assert!(d.distance(&Vector3f(0.0, 2.0, 0.0)) < 1e-5);
+
+    // Perspective divide.
+    let projection = Matrix4f::perspective(90.0, 1.0, 1.0, 10.0);
+    let near = projection.transform_point(&Vector3f(1.0, 1.0, -1.0));
+    assert!(near.distance(&Vector3f(1.0, 1.0, -1.0)) < 1e-5);
+    let far = projection.transform_point(&Vector3f(0.0, 0.0, -10.0));
+    assert!((far.2 - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn project_to_window_coordinates() {
+    let viewport = Viewport {
+        x: 0,
+        y: 0,
+        width: 1024,
+        height: 768,
+    };
+    let projection = Matrix4f::perspective(90.0, 4.0 / 3.0, 1.0, 100.0);
+    let view = Matrix4f::look_at(
+        &Vector3f(0.0, 0.0, 5.0),
+        &Vector3f(0.0, 0.0, 0.0),
+        &Vector3f(0.0, 1.0, 0.0),
+    );
+    let mvp = projection.mul(&view);
+
+    // Look at point is in the center of the window.
+    let center = matrix::project(&Vector3f(0.0, 0.0, 0.0), &mvp, &viewport).unwrap();
+    assert!(center.distance(&Vector3f(512.0, 384.0, center.2)) < 1e-3);
+
+    // Near and far planes.
+    let near = matrix::project(&Vector3f(0.0, 0.0, 4.0), &mvp, &viewport).unwrap();
+    assert!(near.2.abs() < 1e-5);
+    let far = matrix::project(&Vector3f(0.0, 0.0, -95.0), &mvp, &viewport).unwrap();
+    assert!((far.2 - 1.0).abs() < 1e-5);
+
+    // Top right corner of near plane.
+    let corner = matrix::project(&Vector3f(4.0 / 3.0, 1.0, 4.0), &mvp, &viewport).unwrap();
+    assert!(corner.distance(&Vector3f(1024.0, 768.0, 0.0)) < 1e-3);
+
+    // Viewport offset.
+    let offset = Viewport {
+        x: 10,
+        y: 20,
+        width: 100,
+        height: 50,
+    };
+    let moved = matrix::project(&Vector3f(0.0, 0.0, 0.0), &mvp, &offset).unwrap();
+    assert!((moved.0 - 60.0).abs() < 1e-3 && (moved.1 - 45.0).abs() < 1e-3);
+}
+
+#[test]
+fn project_fails_behind_camera() {
+    let viewport = Viewport {
+        x: 0,
+        y: 0,
+        width: 1024,
+        height: 768,
+    };
+    let projection = Matrix4f::perspective(90.0, 4.0 / 3.0, 1.0, 100.0);
+    let view = Matrix4f::look_at(
+        &Vector3f(0.0, 0.0, 5.0),
+        &Vector3f(0.0, 0.0, 0.0),
+        &Vector3f(0.0, 1.0, 0.0),
+    );
+    let mvp = projection.mul(&view);
+
+    // Mirrored point behind camera would land inside window.
+    assert!(matrix::project(&Vector3f(1.0, 1.0, 10.0), &mvp, &viewport).is_none());
+    // Eye plane has zero w.
+    assert!(matrix::project(&Vector3f(1.0, 1.0, 5.0), &mvp, &viewport).is_none());
+    assert!(matrix::project(&Vector3f(1.0, 1.0, 0.0), &mvp, &viewport).is_some());
+}
+
+#[test]
+fn unproject_inverts_project() {
+    let viewport = Viewport {
+        x: 5,
+        y: 10,
+        width: 800,
+        height: 600,
+    };
+    let mvp = Matrix4f::perspective(45.0, 4.0 / 3.0, 0.1, 100.0)
+        .mul(&Matrix4f::look_at(
+            &Vector3f(4.0, 3.0, 3.0),
+            &Vector3f(0.0, 0.0, 0.0),
+            &Vector3f(0.0, 1.0, 0.0),
+        ))
+        .mul(&Matrix4f::rotate(30.0, &Vector3f(0.0, 1.0, 0.0)));
+
+    let points = [
+        Vector3f(0.0, 0.0, 0.0),
+        Vector3f(1.0, -1.0, 0.5),
+        Vector3f(-0.5, 0.25, 1.0),
+    ];
+    for p in points.iter() {
+        let win = matrix::project(p, &mvp, &viewport).unwrap();
+        let obj = matrix::unproject(&win, &mvp, &viewport).unwrap();
+        assert!(obj.distance(p) < 1e-3, "{:?} != {:?}", obj, p);
+    }
+
+    let singular = Matrix4f::scale(&Vector3f(0.0, 1.0, 1.0));
+    assert!(matrix::unproject(&Vector3f(0.0, 0.0, 0.0), &singular, &viewport).is_none());
+}
+
+// Clip-space depth after perspective divide of point at distance z in front of
+// camera.
+fn ndc_depth(projection: &Matrix4f, z: f32) -> f32 {
+    let clip = projection * Vector4f(0.0, 0.0, -z, 1.0);
+
+    clip.2 / clip.3
+}
+
+#[test]
+fn perspective_depth_range() {
+    let projection = Matrix4f::perspective(45.0, 4.0 / 3.0, 0.1, 100.0);
+
+    assert!((ndc_depth(&projection, 0.1) + 1.0).abs() < 1e-5);
+    assert!((ndc_depth(&projection, 100.0) - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn fov_in_degrees_and_radians() {
+    let degrees = Matrix4f::perspective(60.0, 1.5, 1.0, 50.0);
+
+    assert_eq!(
+        Matrix4f::perspective_fov(Deg(60.0), 1.5, 1.0, 50.0),
+        degrees
+    );
+    assert_matrix_eq(
+        &Matrix4f::perspective_fov(Rad(::std::f32::consts::FRAC_PI_3), 1.5, 1.0, 50.0),
+        &degrees,
+    );
+    assert!((Rad::from(Deg(180.0)).0 - ::std::f32::consts::PI).abs() < 1e-6);
+    assert!((Deg::from(Rad(::std::f32::consts::PI)).0 - 180.0).abs() < 1e-4);
+
+    // Point on the top edge of 90 degrees frustum.
+    let projection = Matrix4f::perspective_fov(Deg(90.0), 1.0, 1.0, 10.0);
+    let clip = &projection * Vector4f(0.0, 5.0, -5.0, 1.0);
+    assert!((clip.1 / clip.3 - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn infinite_perspective_depth_range() {
+    let projection = Matrix4f::perspective_infinite(Deg(45.0), 4.0 / 3.0, 0.1);
+
+    assert!((ndc_depth(&projection, 0.1) + 1.0).abs() < 1e-5);
+    assert!(ndc_depth(&projection, 1e6) < 1.0);
+    assert!((ndc_depth(&projection, 1e6) - 1.0).abs() < 1e-5);
+
+    // Far points are closer to 1.
+    assert!(ndc_depth(&projection, 10.0) < ndc_depth(&projection, 1000.0));
+
+    // The same as finite projection except depth.
+    let finite = Matrix4f::perspective(45.0, 4.0 / 3.0, 0.1, 100.0);
+    assert_eq!(projection.row(0), finite.row(0));
+    assert_eq!(projection.row(1), finite.row(1));
+    assert_eq!(projection.row(3), finite.row(3));
+}
+
+#[test]
+fn reversed_z_depth_range() {
+    let projection = Matrix4f::perspective_reversed_z(Deg(45.0), 4.0 / 3.0, 0.1, 100.0);
+
+    assert!((ndc_depth(&projection, 0.1) - 1.0).abs() < 1e-5);
+    assert!(ndc_depth(&projection, 100.0).abs() < 1e-5);
+    assert!(ndc_depth(&projection, 1.0) > ndc_depth(&projection, 10.0));
+
+    let infinite = Matrix4f::perspective_infinite_reversed_z(Deg(45.0), 4.0 / 3.0, 0.1);
+    assert!((ndc_depth(&infinite, 0.1) - 1.0).abs() < 1e-5);
+    assert!(ndc_depth(&infinite, 1e6).abs() < 1e-5);
+    assert!(ndc_depth(&infinite, 1e6) > 0.0);
+}
+
+#[test]
+fn off_center_frustum() {
+    let projection = Matrix4f::frustum(-1.0, 3.0, -2.0, 1.0, 2.0, 20.0);
+
+    assert!((ndc_depth(&projection, 2.0) + 1.0).abs() < 1e-5);
+    assert!((ndc_depth(&projection, 20.0) - 1.0).abs() < 1e-5);
+
+    // Corners of near plane.
+    let lb = projection.transform_point(&Vector3f(-1.0, -2.0, -2.0));
+    assert!(lb.distance(&Vector3f(-1.0, -1.0, -1.0)) < 1e-5);
+    let rt = projection.transform_point(&Vector3f(3.0, 1.0, -2.0));
+    assert!(rt.distance(&Vector3f(1.0, 1.0, -1.0)) < 1e-5);
+
+    // Corner of far plane scales with distance.
+    let far = projection.transform_point(&Vector3f(30.0, 10.0, -20.0));
+    assert!(far.distance(&Vector3f(1.0, 1.0, 1.0)) < 1e-5);
+
+    // Symmetric frustum is the same as perspective.
+    let top = 0.1 * (22.5f32).to_radians().tan();
+    assert_matrix_eq(
+        &Matrix4f::frustum(-top, top, -top, top, 0.1, 100.0),
+        &Matrix4f::perspective(45.0, 1.0, 0.1, 100.0),
+    );
+}
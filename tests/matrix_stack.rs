@@ -0,0 +1,82 @@
+extern crate tutcommon;
+
+use tutcommon::matrix::{Matrix4f, MatrixStack, StackUnderflow, Vector3f};
+
+fn origin_of(m: &Matrix4f) -> Vector3f {
+    m.transform_point(&Vector3f(0.0, 0.0, 0.0))
+}
+
+#[test]
+fn push_and_pop_restore_matrix() {
+    let mut stack = MatrixStack::new();
+    assert_eq!(stack.current(), &Matrix4f::default());
+    assert_eq!(stack.depth(), 0);
+
+    stack.translate(&Vector3f(1.0, 2.0, 3.0));
+    let saved = stack.current().clone();
+
+    stack.push();
+    assert_eq!(stack.depth(), 1);
+    stack.scale(&Vector3f(2.0, 2.0, 2.0));
+    stack.rotate(45.0, &Vector3f(0.0, 1.0, 0.0));
+    assert_ne!(stack.current(), &saved);
+
+    assert_eq!(stack.pop(), Ok(()));
+    assert_eq!(stack.depth(), 0);
+    assert_eq!(stack.current(), &saved);
+}
+
+#[test]
+fn hierarchical_transforms() {
+    // Sun at the origin, planet orbits at distance 10, moon at distance 2
+    // from planet.
+    let mut stack = MatrixStack::new();
+
+    stack.push();
+    stack.rotate(90.0, &Vector3f(0.0, 1.0, 0.0));
+    stack.translate(&Vector3f(10.0, 0.0, 0.0));
+    let planet = origin_of(stack.current());
+    assert!(planet.distance(&Vector3f(0.0, 0.0, -10.0)) < 1e-5);
+
+    stack.push();
+    stack.rotate(90.0, &Vector3f(0.0, 1.0, 0.0));
+    stack.translate(&Vector3f(2.0, 0.0, 0.0));
+    let moon = origin_of(stack.current());
+    assert!(moon.distance(&Vector3f(-2.0, 0.0, -10.0)) < 1e-5);
+
+    stack.pop().unwrap();
+    assert!(origin_of(stack.current()).distance(&planet) < 1e-5);
+    stack.pop().unwrap();
+    assert_eq!(stack.current(), &Matrix4f::default());
+}
+
+#[test]
+fn load_and_multiply() {
+    let mut stack = MatrixStack::new();
+    let m = Matrix4f::translate(&Vector3f(1.0, 0.0, 0.0));
+
+    stack.load(m.clone());
+    assert_eq!(stack.current(), &m);
+
+    stack.multiply(&Matrix4f::scale(&Vector3f(3.0, 3.0, 3.0)));
+    assert_eq!(
+        stack.current(),
+        &m.mul(&Matrix4f::scale(&Vector3f(3.0, 3.0, 3.0)))
+    );
+
+    stack.load_identity();
+    assert_eq!(stack.current(), &Matrix4f::default());
+}
+
+#[test]
+fn unbalanced_pop_is_error() {
+    let mut stack = MatrixStack::new();
+    stack.translate(&Vector3f(1.0, 0.0, 0.0));
+    let current = stack.current().clone();
+
+    stack.push();
+    assert!(stack.pop().is_ok());
+    assert_eq!(stack.pop(), Err(StackUnderflow));
+    assert_eq!(stack.current(), &current);
+    assert_eq!(StackUnderflow.to_string(), "matrix stack underflow");
+}
@@ -0,0 +1,556 @@
+extern crate tutcommon;
+
+use std::f32::consts::PI;
+
+use tutcommon::{
+    geometry::Aabb,
+    matrix::{ApproxEq, Vector2f, Vector3f, Vector4f},
+    mesh::{Mesh, MeshBuilder, NormalOptions, NormalWeighting, Submesh},
+    objloader,
+};
+
+#[test]
+fn builder_without_indices() {
+    let mut builder = MeshBuilder::new();
+    builder.vertex(Vector3f(0.0, 0.0, 0.0), Some(Vector2f(0.0, 0.0)), None);
+    builder.vertex(Vector3f(1.0, 0.0, 0.0), None, None);
+    builder.vertex(Vector3f(0.0, 2.0, -1.0), Some(Vector2f(0.0, 1.0)), None);
+    let mesh = builder.build();
+
+    assert_eq!(mesh.vertex_count(), 3);
+    assert_eq!(mesh.triangle_count(), 1);
+    assert_eq!(mesh.indices, None);
+    // Missing UV is filled, normals are missing everywhere.
+    assert_eq!(mesh.uvs[1], Vector2f(0.0, 0.0));
+    assert!(mesh.normals.is_empty());
+    assert_eq!(
+        mesh.bounds,
+        Some(Aabb::new(Vector3f(0.0, 0.0, -1.0), Vector3f(1.0, 2.0, 0.0)))
+    );
+    assert_eq!(
+        mesh.submeshes,
+        vec![Submesh {
+            range: 0..3,
+            name: None,
+            material: None,
+        }]
+    );
+}
+
+#[test]
+fn builder_with_indices() {
+    let mut builder = MeshBuilder::new();
+    let n = Some(Vector3f(0.0, 0.0, 1.0));
+    let a = builder.vertex(Vector3f(0.0, 0.0, 0.0), None, n);
+    let b = builder.vertex(Vector3f(1.0, 0.0, 0.0), None, n);
+    let c = builder.vertex(Vector3f(1.0, 1.0, 0.0), None, n);
+    let d = builder.vertex(Vector3f(0.0, 1.0, 0.0), None, n);
+    builder.triangle(a, b, c).submesh().triangle(a, c, d);
+    let mesh = builder.build();
+
+    assert_eq!(mesh.vertex_count(), 4);
+    assert_eq!(mesh.element_count(), 6);
+    assert_eq!(mesh.triangle_count(), 2);
+    assert_eq!(mesh.indices, Some(vec![0, 1, 2, 0, 2, 3]));
+    assert!(mesh.uvs.is_empty());
+    assert_eq!(mesh.normals.len(), 4);
+    assert_eq!(
+        mesh.submeshes,
+        vec![
+            Submesh {
+                range: 0..3,
+                name: None,
+                material: None,
+            },
+            Submesh {
+                range: 3..6,
+                name: None,
+                material: None,
+            }
+        ]
+    );
+}
+
+#[test]
+fn empty_mesh() {
+    let mesh = MeshBuilder::new().build();
+
+    assert_eq!(mesh, Mesh::default());
+    assert_eq!(mesh.bounds, None);
+    assert!(mesh.submeshes.is_empty());
+}
+
+#[test]
+fn load_cube() {
+    let mut mesh = objloader::obj_load("data/tut07/cube.obj").unwrap();
+
+    assert_eq!(mesh.vertex_count(), 36);
+    assert_eq!(mesh.triangle_count(), 12);
+    assert_eq!(mesh.uvs.len(), 36);
+    assert_eq!(mesh.normals.len(), 36);
+    assert_eq!(
+        mesh.submeshes,
+        vec![Submesh {
+            range: 0..36,
+            name: None,
+            material: Some(0),
+        }]
+    );
+
+    let bounds = mesh.bounds.unwrap();
+    assert!(bounds.min.abs_diff_eq(&Vector3f(-1.0, -1.0, -1.0), 1e-5));
+    assert!(bounds.max.abs_diff_eq(&Vector3f(1.0, 1.0, 1.0), 1e-5));
+
+    // The first face is `f 5/1/1 1/2/1 4/3/1`.
+    assert_eq!(mesh.positions[0], Vector3f(1.0, 1.0, -1.0));
+    assert_eq!(mesh.uvs[0], Vector2f(0.748573, 0.750412));
+    assert_eq!(mesh.normals[0], Vector3f(0.0, 0.0, -1.0));
+
+    mesh.invert_v();
+    assert_eq!(mesh.uvs[0], Vector2f(0.748573, -0.750412));
+}
+
+#[test]
+fn indexing_merges_duplicates() {
+    let mesh = objloader::obj_load("data/tut07/cube.obj").unwrap();
+    let indexed = mesh.to_indexed(0.0);
+
+    // Cube has 31 different `v/vt/vn` triples, but some of them refer to
+    // equal values.
+    assert_eq!(indexed.vertex_count(), 28);
+    assert_eq!(indexed.element_count(), 36);
+    assert_eq!(indexed.triangle_count(), 12);
+    assert!((indexed.compression_ratio() - 36.0 / 28.0).abs() < 1e-6);
+    assert_eq!(indexed.bounds, mesh.bounds);
+    assert_eq!(indexed.submeshes, mesh.submeshes);
+
+    // Triangles are the same.
+    let indices = indexed.indices.as_ref().unwrap();
+    for (i, &index) in indices.iter().enumerate() {
+        let index = index as usize;
+        assert_eq!(indexed.positions[index], mesh.positions[i]);
+        assert_eq!(indexed.uvs[index], mesh.uvs[i]);
+        assert_eq!(indexed.normals[index], mesh.normals[i]);
+    }
+    assert_eq!(
+        indexed.indices_u16().unwrap(),
+        indices.iter().map(|&i| i as u16).collect::<Vec<_>>()
+    );
+
+    // Indexing again changes nothing.
+    assert_eq!(indexed.to_indexed(0.0), indexed);
+
+    let suzanne = objloader::obj_load("data/tut08/suzanne.obj").unwrap();
+    let indexed = suzanne.to_indexed(1e-4);
+    assert_eq!(indexed.element_count(), 968 * 3);
+    assert!(
+        indexed.compression_ratio() > 2.5,
+        "{}",
+        indexed.compression_ratio()
+    );
+}
+
+#[test]
+fn indexing_with_epsilon() {
+    let mut builder = MeshBuilder::new();
+    for &x in [0.0, 1.0, 1.000_001, 0.0, 1.0, 2.0].iter() {
+        builder.vertex(Vector3f(x, x, 0.0), None, None);
+    }
+    let mesh = builder.build();
+    assert_eq!(mesh.compression_ratio(), 1.0);
+
+    let exact = mesh.to_indexed(0.0);
+    assert_eq!(exact.indices, Some(vec![0, 1, 2, 0, 1, 3]));
+    assert!(exact.uvs.is_empty() && exact.normals.is_empty());
+
+    let merged = mesh.to_indexed(1e-4);
+    assert_eq!(merged.indices, Some(vec![0, 1, 1, 0, 1, 2]));
+    assert_eq!(merged.positions[1], Vector3f(1.0, 1.0, 0.0));
+    assert_eq!(merged.compression_ratio(), 2.0);
+}
+
+#[test]
+fn indexing_merges_across_cell_boundary() {
+    // 0.00015 and 0.00025 are rounded to different cells of 1e-4 grid.
+    let mut builder = MeshBuilder::new();
+    for &x in [0.000_15, 0.000_25, 0.000_35, 0.000_15, 0.000_25, 0.000_35].iter() {
+        builder.vertex(Vector3f(x, 1.0, -x), Some(Vector2f(x, 0.5)), None);
+    }
+    let merged = builder.build().to_indexed(1e-4 + 1e-6);
+    // The last one is too far from the first kept vertex.
+    assert_eq!(merged.indices, Some(vec![0, 0, 1, 0, 0, 1]));
+    assert_eq!(merged.positions[0], Vector3f(0.000_15, 1.0, -0.000_15));
+}
+
+#[test]
+fn indexing_merges_signed_zeros() {
+    let mut builder = MeshBuilder::new();
+    for &(x, z) in [(0.0, 1.0), (-0.0, 1.0), (0.0, -0.0)].iter() {
+        builder.vertex(Vector3f(x, 0.0, z), None, Some(Vector3f(x, -0.0, 1.0)));
+    }
+    builder.vertex(
+        Vector3f(-0.0, 0.0, 0.0),
+        None,
+        Some(Vector3f(0.0, 0.0, 1.0)),
+    );
+    let mesh = builder.build();
+
+    for &epsilon in [0.0, 1e-4].iter() {
+        let indexed = mesh.to_indexed(epsilon);
+        assert_eq!(indexed.indices, Some(vec![0, 0, 1, 1]), "{}", epsilon);
+    }
+}
+
+#[test]
+fn large_indices() {
+    let mut builder = MeshBuilder::new();
+    for i in 0..70_000 {
+        let index = builder.vertex(Vector3f(i as f32, 0.0, 0.0), None, None);
+        builder.triangle(index, index, index);
+    }
+    let mesh = builder.build();
+
+    assert_eq!(mesh.indices.as_ref().unwrap()[69_999 * 3], 69_999);
+    assert_eq!(mesh.indices_u16(), None);
+    assert_eq!(MeshBuilder::new().build().indices_u16(), None);
+}
+
+#[test]
+fn smoothing_group_normals() {
+    // Normals are generated by loader.
+    let mesh = objloader::obj_load("data/obj/groups.obj").unwrap();
+    assert_eq!(mesh.normals.len(), 21);
+    // Floor and side are flat, slopes of the roof are smooth.
+    let floor = Vector3f(0.0, -1.0, 0.0);
+    let side = Vector3f(-1.0, 0.0, 0.0);
+    let front = Vector3f(0.0, 1.0, -2.0) / 5f32.sqrt();
+    let ridge = Vector3f(0.0, 1.5, 1.0) / 3.25f32.sqrt();
+    for (i, expected) in [(0, floor), (5, floor), (6, front), (7, ridge), (14, ridge)]
+        .iter()
+        .chain([(18, side), (19, side), (20, side)].iter())
+    {
+        assert!(
+            mesh.normals[*i].abs_diff_eq(expected, 1e-5),
+            "{}: {:?}",
+            i,
+            mesh.normals[*i]
+        );
+    }
+
+    // Vertices are split where normals differ.
+    let mut positions = mesh.clone();
+    positions.normals.clear();
+    let mut indexed = positions.to_indexed(0.0);
+    assert_eq!(indexed.vertex_count(), 6);
+    indexed.generate_normals();
+    assert_eq!(indexed.vertex_count(), 13);
+    assert_eq!(indexed.triangle_count(), 7);
+    assert_eq!(indexed.normals.len(), 13);
+    let indices = indexed.indices.as_ref().unwrap();
+    for (i, &index) in indices.iter().enumerate() {
+        assert_eq!(indexed.positions[index as usize], mesh.positions[i]);
+        assert_eq!(indexed.normals[index as usize], mesh.normals[i]);
+    }
+}
+
+// Indexed UV sphere of radius 1, with degenerate triangles at poles.
+fn sphere(rings: usize, segments: usize) -> Mesh {
+    let mut builder = MeshBuilder::new();
+    for i in 0..=rings {
+        let (sin, cos) = match i {
+            0 => (0.0, 1.0),
+            _ if i == rings => (0.0, -1.0),
+            _ => (i as f32 * PI / rings as f32).sin_cos(),
+        };
+        for j in 0..=segments {
+            let phi = (j % segments) as f32 * 2.0 * PI / segments as f32;
+            builder.vertex(Vector3f(sin * phi.cos(), cos, sin * phi.sin()), None, None);
+        }
+    }
+
+    let columns = segments as u32 + 1;
+    for i in 0..rings as u32 {
+        for j in 0..segments as u32 {
+            let a = i * columns + j;
+            let b = a + columns;
+            builder.triangle(a, b + 1, b).triangle(a, a + 1, b + 1);
+        }
+    }
+
+    builder.build()
+}
+
+fn assert_same_normals(a: &Mesh, b: &Mesh) {
+    assert_eq!(a.normals.len(), b.normals.len());
+    for (i, (n, m)) in a.normals.iter().zip(&b.normals).enumerate() {
+        assert!(n.abs_diff_eq(m, 1e-5), "{}: {:?} != {:?}", i, n, m);
+    }
+}
+
+#[test]
+fn cube_normals() {
+    let cube = objloader::obj_load("data/tut07/cube.obj").unwrap();
+    assert_eq!(cube.smoothing_groups, vec![0; 12]);
+    // Without `s off` of the file normals depend on crease angle.
+    let mut mesh = cube.clone();
+    mesh.smoothing_groups.clear();
+
+    // Edges of cube are creases.
+    mesh.generate_normals();
+    assert_same_normals(&mesh, &cube);
+
+    let flat = NormalOptions {
+        smooth: false,
+        crease_angle: PI,
+        ..NormalOptions::default()
+    };
+    mesh.generate_normals_with(&flat);
+    assert_same_normals(&mesh, &cube);
+
+    // Creases split vertices of indexed cube, one corner is off by 1e-6.
+    let mut positions = mesh.clone();
+    positions.uvs.clear();
+    positions.normals.clear();
+    for p in &mut positions.positions {
+        *p = Vector3f(p.0.round(), p.1.round(), p.2.round());
+    }
+    let mut indexed = positions.to_indexed(0.0);
+    assert_eq!(indexed.vertex_count(), 8);
+    indexed.generate_normals();
+    assert_eq!(indexed.vertex_count(), 24);
+    assert_eq!(indexed.triangle_count(), 12);
+
+    // Every face has right angle at corner, so corners point outwards.
+    let smooth = NormalOptions {
+        weighting: NormalWeighting::Angle,
+        crease_angle: PI,
+        ..NormalOptions::default()
+    };
+    let mut indexed = positions.to_indexed(0.0);
+    indexed.generate_normals_with(&smooth);
+    assert_eq!(indexed.vertex_count(), 8);
+    for (p, n) in indexed.positions.iter().zip(&indexed.normals) {
+        assert!(n.abs_diff_eq(&p.normalize(), 1e-5), "{:?} {:?}", p, n);
+    }
+}
+
+#[test]
+fn sphere_normals() {
+    for &weighting in [NormalWeighting::Area, NormalWeighting::Angle].iter() {
+        let mut mesh = sphere(16, 32);
+        mesh.generate_normals_with(&NormalOptions {
+            weighting,
+            ..NormalOptions::default()
+        });
+
+        // Only the seam is split.
+        assert_eq!(mesh.vertex_count(), 17 * 33);
+        for (p, n) in mesh.positions.iter().zip(&mesh.normals) {
+            assert!((n.length() - 1.0).abs() < 1e-5);
+            assert!(n.dot(p) > 0.999, "{:?}: {:?} {:?}", weighting, p, n);
+        }
+    }
+}
+
+#[test]
+fn plane_tangents() {
+    // Quad in XY plane, the right half has mirrored U.
+    let mut builder = MeshBuilder::new();
+    let n = Some(Vector3f(0.0, 0.0, 1.0));
+    let mut vertex =
+        |x: f32, y: f32, u: f32| builder.vertex(Vector3f(x, y, 0.0), Some(Vector2f(u, y)), n);
+    let (a, b, c, d) = (
+        vertex(0.0, 0.0, 0.0),
+        vertex(1.0, 0.0, 1.0),
+        vertex(1.0, 1.0, 1.0),
+        vertex(0.0, 1.0, 0.0),
+    );
+    let (e, f) = (vertex(2.0, 0.0, 0.0), vertex(2.0, 1.0, 0.0));
+    builder.triangle(a, b, c).triangle(a, c, d);
+    builder.triangle(b, e, f).triangle(b, f, c);
+    let mut mesh = builder.build();
+    assert_eq!(mesh.vertex_count(), 6);
+
+    mesh.generate_tangents();
+    // Vertices on mirror line are split.
+    assert_eq!(mesh.vertex_count(), 8);
+    assert_eq!(mesh.tangents.len(), 8);
+    let indices = mesh.indices.clone().unwrap();
+    for (i, &index) in indices.iter().enumerate() {
+        let expected = if i < 6 {
+            Vector4f(1.0, 0.0, 0.0, 1.0)
+        } else {
+            Vector4f(-1.0, 0.0, 0.0, -1.0)
+        };
+        assert_eq!(mesh.tangents[index as usize], expected);
+    }
+
+    // Tangents need UVs and normals.
+    let mut mesh = sphere(4, 8);
+    mesh.generate_tangents();
+    assert!(mesh.tangents.is_empty());
+}
+
+// Derivatives of position by U and V in each triangle.
+fn uv_derivatives(mesh: &Mesh) -> Vec<Option<(Vector3f, Vector3f)>> {
+    mesh.positions
+        .chunks(3)
+        .zip(mesh.uvs.chunks(3))
+        .map(|(p, uv)| {
+            let (e1, e2) = (p[1] - p[0], p[2] - p[0]);
+            let (t1, t2) = (uv[1] - uv[0], uv[2] - uv[0]);
+            let area = t1.0 * t2.1 - t2.0 * t1.1;
+            if area.abs() < 1e-6 {
+                return None;
+            }
+
+            let dpdu = (e1 * t2.1 - e2 * t1.1) / area;
+            let dpdv = (e2 * t1.0 - e1 * t2.0) / area;
+            Some((dpdu.normalize(), dpdv.normalize()))
+        })
+        .collect()
+}
+
+#[test]
+fn loaded_tangents() {
+    for path in ["data/tut07/cube.obj", "data/tut08/suzanne.obj"].iter() {
+        let mut mesh = objloader::obj_load(path).unwrap();
+        mesh.generate_tangents();
+        assert_eq!(mesh.tangents.len(), mesh.vertex_count());
+
+        for (n, t) in mesh.normals.iter().zip(&mesh.tangents) {
+            let tangent = Vector3f(t.0, t.1, t.2);
+            assert!(
+                (tangent.length() - 1.0).abs() < 1e-5,
+                "{}: {:?} {:?}",
+                path,
+                n,
+                t
+            );
+            assert!(tangent.dot(n).abs() < 1e-5);
+            assert!(t.3 == 1.0 || t.3 == -1.0);
+        }
+
+        // Indexing keeps tangents.
+        let indexed = mesh.to_indexed(0.0);
+        assert_eq!(indexed.tangents.len(), indexed.vertex_count());
+    }
+
+    // Faces of cube are flat, so frame follows their UVs.
+    let mut cube = objloader::obj_load("data/tut07/cube.obj").unwrap();
+    cube.generate_tangents();
+    for (i, derivatives) in uv_derivatives(&cube).into_iter().enumerate() {
+        let (dpdu, dpdv) = derivatives.unwrap();
+        for j in 3 * i..3 * i + 3 {
+            let (n, t) = (cube.normals[j], cube.tangents[j]);
+            let tangent = Vector3f(t.0, t.1, t.2);
+            let bitangent = n.cross(&tangent) * t.3;
+            assert!(tangent.dot(&dpdu) > 0.999, "{}: {:?}", j, t);
+            assert!(bitangent.dot(&dpdv) > 0.999, "{}: {:?}", j, t);
+        }
+    }
+}
+
+#[test]
+fn reference_tangents() {
+    // Output of reference MikkTSpace implementation for each corner.
+    let expected = [
+        Vector4f(0.8, 0.0, 0.6, 1.0),
+        Vector4f(1.0, 0.0, 0.0, 1.0),
+        Vector4f(1.0, 0.0, 0.0, 1.0),
+        Vector4f(0.8, 0.0, 0.6, 1.0),
+        Vector4f(1.0, 0.0, 0.0, 1.0),
+        Vector4f(0.8, 0.0, 0.6, 1.0),
+        Vector4f(1.0, 0.0, 0.0, 1.0),
+        Vector4f(0.8, 0.0, -0.6, 1.0),
+        Vector4f(0.970_655_6, 0.192_378_88, -0.144_284_14, 1.0),
+        Vector4f(1.0, 0.0, 0.0, 1.0),
+        Vector4f(0.970_655_6, 0.192_378_88, -0.144_284_14, 1.0),
+        Vector4f(1.0, 0.0, 0.0, 1.0),
+        Vector4f(-0.8, 0.0, 0.6, -1.0),
+        Vector4f(-1.0, 0.0, 0.0, -1.0),
+        Vector4f(-1.0, 0.0, 0.0, -1.0),
+        Vector4f(-0.8, 0.0, 0.6, -1.0),
+        Vector4f(-1.0, 0.0, 0.0, -1.0),
+        Vector4f(-0.984_183_25, 0.141_722_4, -0.106_291_79, -1.0),
+    ];
+
+    let mut mesh = objloader::obj_load("data/obj/tangents.obj").unwrap();
+    let mut indexed = mesh.to_indexed(0.0);
+    mesh.generate_tangents();
+    for (i, (t, e)) in mesh.tangents.iter().zip(expected.iter()).enumerate() {
+        assert!(t.abs_diff_eq(e, 1e-5), "{}: {:?} != {:?}", i, t, e);
+    }
+
+    // Vertices on mirror line are split.
+    let vertex_count = indexed.vertex_count();
+    indexed.generate_tangents();
+    assert_eq!(indexed.vertex_count(), vertex_count + 2);
+    let indices = indexed.indices.as_ref().unwrap();
+    for (i, (&index, e)) in indices.iter().zip(expected.iter()).enumerate() {
+        let t = indexed.tangents[index as usize];
+        assert!(t.abs_diff_eq(e, 1e-5), "{}: {:?} != {:?}", i, t, e);
+    }
+}
+
+#[test]
+fn normals_after_tangents() {
+    let mut cube = objloader::obj_load("data/tut07/cube.obj")
+        .unwrap()
+        .to_indexed(0.0);
+    cube.smoothing_groups.clear();
+    cube.generate_tangents();
+    let vertex_count = cube.vertex_count();
+
+    // Smooth normals over all edges change directions, but not vertices.
+    cube.generate_normals_with(&NormalOptions {
+        crease_angle: PI,
+        ..NormalOptions::default()
+    });
+    assert_eq!(cube.vertex_count(), vertex_count);
+    assert_eq!(cube.tangents.len(), vertex_count);
+    for (n, t) in cube.normals.iter().zip(&cube.tangents) {
+        assert!(
+            Vector3f(t.0, t.1, t.2).dot(n).abs() < 1e-5,
+            "{:?} {:?}",
+            n,
+            t
+        );
+    }
+
+    // Quad folded along diagonal, flat normals split vertices on the fold and
+    // tangents follow them.
+    let mut builder = MeshBuilder::new();
+    let n = Some(Vector3f(0.0, 0.0, 1.0));
+    let mut vertex = |p: Vector3f, u: f32, v: f32| builder.vertex(p, Some(Vector2f(u, v)), n);
+    let (a, b, c, d) = (
+        vertex(Vector3f(0.0, 0.0, 0.0), 0.0, 0.0),
+        vertex(Vector3f(1.0, 0.0, 0.0), 1.0, 0.0),
+        vertex(Vector3f(1.0, 1.0, 0.0), 1.0, 1.0),
+        vertex(Vector3f(0.0, 1.0, 1.0), 0.0, 1.0),
+    );
+    builder.triangle(a, b, c).triangle(a, c, d);
+    let mut quad = builder.build();
+    quad.generate_tangents();
+    assert_eq!(quad.tangents.len(), 4);
+
+    quad.generate_normals_with(&NormalOptions {
+        smooth: false,
+        ..NormalOptions::default()
+    });
+    assert_eq!(quad.vertex_count(), 6);
+    assert_eq!(quad.tangents.len(), 6);
+    for (n, t) in quad.normals.iter().zip(&quad.tangents) {
+        assert!(
+            Vector3f(t.0, t.1, t.2).dot(n).abs() < 1e-5,
+            "{:?} {:?}",
+            n,
+            t
+        );
+    }
+
+    // Mesh without tangents doesn't get them.
+    let mut mesh = objloader::obj_load("data/tut07/cube.obj").unwrap();
+    mesh.generate_normals();
+    assert!(mesh.tangents.is_empty());
+}
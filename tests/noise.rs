@@ -0,0 +1,172 @@
+extern crate tutcommon;
+
+use tutcommon::noise::{
+    fill_image, fill_image_f32, Fbm, Noise, Perlin, RidgedMulti, Simplex, Worley,
+};
+
+const POINTS: [(f32, f32, f32); 3] = [(0.3, 0.7, 1.1), (12.5, -3.25, 0.75), (-7.9, 4.4, -2.2)];
+
+// Values at `POINTS` for seed 42.
+fn assert_snapshot<N: Noise>(noise: &N, expected2: [f32; 3], expected3: [f32; 3]) {
+    for (i, &(x, y, z)) in POINTS.iter().enumerate() {
+        let (v2, v3) = (noise.noise2(x, y), noise.noise3(x, y, z));
+        assert!(
+            (v2 - expected2[i]).abs() < 1e-6,
+            "{}: {} != {}",
+            i,
+            v2,
+            expected2[i]
+        );
+        assert!(
+            (v3 - expected3[i]).abs() < 1e-6,
+            "{}: {} != {}",
+            i,
+            v3,
+            expected3[i]
+        );
+    }
+}
+
+// Values on grid are in range and change smoothly.
+fn assert_range<N: Noise>(noise: &N, min: f32, max: f32) {
+    for i in 0..2000 {
+        let (x, y, z) = (
+            (i % 50) as f32 * 0.37 - 9.0,
+            (i / 50) as f32 * 0.41 - 8.0,
+            0.3,
+        );
+        for &v in [noise.noise2(x, y), noise.noise3(x, y, z)].iter() {
+            assert!((min..=max).contains(&v), "{} at {} {}", v, x, y);
+        }
+
+        let d2 = (noise.noise2(x + 1e-3, y) - noise.noise2(x, y)).abs();
+        let d3 = (noise.noise3(x, y, z + 1e-3) - noise.noise3(x, y, z)).abs();
+        assert!(d2 < 1e-2 && d3 < 1e-2, "{} {} at {} {}", d2, d3, x, y);
+    }
+}
+
+#[test]
+fn perlin() {
+    let noise = Perlin::new(42);
+    assert_snapshot(
+        &noise,
+        [0.26843685, -0.48706055, 0.22713925],
+        [-0.33443466, 0.14544249, -0.23638505],
+    );
+    assert_range(&noise, -1.0, 1.0);
+
+    // Gradient noise is zero on lattice points.
+    assert_eq!(noise.noise2(3.0, -5.0), 0.0);
+    assert_eq!(noise.noise3(3.0, -5.0, 7.0), 0.0);
+}
+
+#[test]
+fn simplex() {
+    let noise = Simplex::new(42);
+    assert_snapshot(
+        &noise,
+        [0.895458, 0.40094614, 0.4993337],
+        [0.6925723, -0.007518541, 0.017437464],
+    );
+    assert_range(&noise, -1.0, 1.0);
+}
+
+#[test]
+fn worley() {
+    let noise = Worley::new(42);
+    assert_snapshot(
+        &noise,
+        [0.37565258, 0.34439394, 0.6610535],
+        [0.40877208, 0.3585182, 0.70942426],
+    );
+    assert_range(&noise, 0.0, 1.8);
+}
+
+#[test]
+fn fractal() {
+    let fbm = Fbm::new(Perlin::new(42), 4);
+    assert_snapshot(
+        &fbm,
+        [0.19646676, -0.25976563, 0.24082564],
+        [-0.11053845, -0.022430675, -0.0047575114],
+    );
+    assert_range(&Fbm::new(Perlin::new(42), 1), -1.0, 1.0);
+
+    // Single octave is the source noise.
+    let (x, y) = (1.3, -2.9);
+    assert_eq!(
+        Fbm::new(Simplex::new(7), 1).noise2(x, y),
+        Simplex::new(7).noise2(x, y)
+    );
+
+    let ridged = RidgedMulti::new(Simplex::new(42), 4);
+    assert_snapshot(
+        &ridged,
+        [-0.98650444, -0.1735788, -0.5665479],
+        [-0.879608, 0.5964855, 0.5706041],
+    );
+    for i in 0..1000 {
+        let v = ridged.noise2(i as f32 * 0.13, i as f32 * 0.07);
+        assert!((-1.0..=1.0).contains(&v), "{}", v);
+    }
+}
+
+#[test]
+fn seeds() {
+    let (x, y, z) = (0.3, 0.7, 1.1);
+    assert_eq!(Perlin::new(1), Perlin::new(1));
+    assert_ne!(Perlin::new(1).noise2(x, y), Perlin::new(2).noise2(x, y));
+    assert_ne!(
+        Simplex::new(1).noise3(x, y, z),
+        Simplex::new(2).noise3(x, y, z)
+    );
+    assert_ne!(Worley::new(1).noise2(x, y), Worley::new(2).noise2(x, y));
+}
+
+#[test]
+fn images() {
+    let noise = Perlin::new(42);
+
+    let mut rgb = vec![0; 4 * 2 * 3];
+    fill_image(&noise, &mut rgb, 4, 3, 0.37);
+    assert_eq!(
+        rgb,
+        vec![
+            148, 148, 148, 109, 109, 109, 92, 92, 92, 144, 144, 144, 167, 167, 167, 122, 122, 122,
+            68, 68, 68, 83, 83, 83,
+        ]
+    );
+
+    let mut red = vec![0.0; 4 * 2];
+    fill_image_f32(&noise, &mut red, 4, 0.37);
+    assert_eq!(red[5], noise.noise2(1.5 * 0.37, 1.5 * 0.37));
+    for (v, c) in red.iter().zip(rgb.chunks(3)) {
+        assert_eq!(((v + 1.0) * 127.5).round() as u8, c[0]);
+    }
+}
+
+#[test]
+fn image_sizes() {
+    let noise = Perlin::new(42);
+
+    // Zero sized images are valid.
+    fill_image(&noise, &mut [], 0, 3, 0.37);
+    fill_image(&noise, &mut [], 4, 0, 0.37);
+    fill_image_f32(&noise, &mut [], 0, 0.37);
+    let mut untouched = [7; 3];
+    fill_image(&noise, &mut untouched, 0, 3, 0.37);
+    assert_eq!(untouched, [7; 3]);
+
+    // Incomplete last row is filled like complete one.
+    let mut full = vec![0.0; 4 * 2];
+    fill_image_f32(&noise, &mut full, 4, 0.37);
+    let mut partial = vec![0.0; 4 + 3];
+    fill_image_f32(&noise, &mut partial, 4, 0.37);
+    assert_eq!(partial[..], full[..7]);
+
+    let mut full = vec![0; 4 * 2 * 3];
+    fill_image(&noise, &mut full, 4, 3, 0.37);
+    let mut partial = vec![0; 4 * 3 + 5];
+    fill_image(&noise, &mut partial, 4, 3, 0.37);
+    assert_eq!(partial[..], full[..17]);
+}
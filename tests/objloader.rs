@@ -0,0 +1,351 @@
+extern crate tutcommon;
+
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use tutcommon::{
+    color::Rgb,
+    matrix::{Vector2f, Vector3f},
+    mesh::Mesh,
+    objloader::{
+        mtl_load, obj_load, obj_load_with, ObjError, ObjErrorKind, ObjOptions, Triangulation,
+    },
+};
+
+const VERTICES: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\n";
+
+// Load OBJ source from temporary file.
+fn load(name: &str, source: &str) -> Result<usize, ObjError> {
+    let path = env::temp_dir().join(format!("tutcommon-{}.obj", name));
+    fs::write(&path, source).unwrap();
+    let res = obj_load(&path).map(|mesh| mesh.triangle_count());
+    fs::remove_file(&path).unwrap();
+
+    res
+}
+
+fn location(e: &ObjError) -> (usize, usize) {
+    (e.line, e.column)
+}
+
+#[test]
+fn valid_file() {
+    let source = format!("# comment\n{}s off\nf 1/1/1 2/1/1 3/1/1\n\n", VERTICES);
+    assert_eq!(load("valid", &source).unwrap(), 1);
+}
+
+#[test]
+fn bad_numbers() {
+    let e = load("bad-float", "v 0 0 0\nv 1 x1 0\n").unwrap_err();
+    assert_eq!(location(&e), (2, 5));
+    match e.kind {
+        ObjErrorKind::BadFloat(ref s) => assert_eq!(s, "x1"),
+        ref kind => panic!("{:?}", kind),
+    }
+    assert!(e
+        .to_string()
+        .ends_with("tutcommon-bad-float.obj:2:5: bad float `x1`"));
+
+    let e = load("bad-index", &format!("{}f 1/1/1 2/a/1 3/1/1\n", VERTICES)).unwrap_err();
+    assert_eq!(location(&e), (6, 11));
+    match e.kind {
+        ObjErrorKind::BadIndex(ref s) => assert_eq!(s, "a"),
+        ref kind => panic!("{:?}", kind),
+    }
+}
+
+#[test]
+fn missing_components() {
+    let e = load("missing-z", "v 1 2\n").unwrap_err();
+    assert_eq!(location(&e), (1, 6));
+    match e.kind {
+        ObjErrorKind::MissingComponent(what) => assert_eq!(what, "vertex z"),
+        ref kind => panic!("{:?}", kind),
+    }
+
+    let e = load("missing-uv", &format!("{}f 1/ 2 3\n", VERTICES)).unwrap_err();
+    assert_eq!(location(&e), (6, 5));
+    match e.kind {
+        ObjErrorKind::MissingComponent(what) => assert_eq!(what, "texture index"),
+        ref kind => panic!("{:?}", kind),
+    }
+
+    let e = load("missing-vertex", &format!("{}f 1/1/1 2/1/1\n", VERTICES)).unwrap_err();
+    assert_eq!(location(&e), (6, 14));
+}
+
+#[test]
+fn index_out_of_range() {
+    for &(face, column, index) in [
+        ("f 1/1/1 2/1/1 4/1/1", 15, 4),
+        ("f 1/1/1 2/2/1 3/1/1", 11, 2),
+        ("f 1/1/1 2/1/1 3/1/0", 19, 0),
+    ]
+    .iter()
+    {
+        let e = load("out-of-range", &format!("{}{}\n", VERTICES, face)).unwrap_err();
+        assert_eq!(location(&e), (6, column), "{}", face);
+        match e.kind {
+            ObjErrorKind::IndexOutOfRange(i) => assert_eq!(i, index),
+            ref kind => panic!("{:?}", kind),
+        }
+    }
+}
+
+#[test]
+fn unsupported_directive() {
+    let e = load("unsupported", &format!("{}  curve 0 1 1 2\n", VERTICES)).unwrap_err();
+    assert_eq!(location(&e), (6, 3));
+    match e.kind {
+        ObjErrorKind::UnsupportedDirective(ref s) => assert_eq!(s, "curve"),
+        ref kind => panic!("{:?}", kind),
+    }
+}
+
+#[test]
+fn skipped_directives() {
+    // Loose edge as Blender writes it, point, curve and render attributes.
+    let source = format!(
+        "{}f 1/1/1 2/1/1 3/1/1\nl 1 2\np 3\nvp 0.5\ncstype bspline\ndeg 3\n\
+         curv 0 1 1 2\nend\nmg 1 0.5\nbevel off\nlod 1\nshadow_obj shadow.obj\n",
+        VERTICES
+    );
+    assert_eq!(load("skipped", &source).unwrap(), 1);
+}
+
+#[test]
+fn missing_file() {
+    let path = PathBuf::from("data/missing.obj");
+    let e = obj_load(&path).unwrap_err();
+
+    assert_eq!(e.file, path);
+    assert_eq!(location(&e), (0, 0));
+    assert!(e.source().is_some());
+}
+
+// Signed areas of triangles in XY plane.
+fn areas(mesh: &Mesh) -> Vec<f32> {
+    mesh.positions
+        .chunks(3)
+        .map(|t| (t[1] - t[0]).cross(&(t[2] - t[0])).2 / 2.0)
+        .collect()
+}
+
+#[test]
+fn face_formats() {
+    let mesh = obj_load("data/obj/positions.obj").unwrap();
+    assert_eq!(mesh.triangle_count(), 1);
+    assert!(mesh.uvs.is_empty());
+    // Missing normals are generated, unless disabled.
+    assert_eq!(mesh.normals, vec![Vector3f(0.0, 0.0, 1.0); 3]);
+    let without_normals = ObjOptions {
+        normals: None,
+        ..ObjOptions::default()
+    };
+    let mesh = obj_load_with("data/obj/positions.obj", &without_normals).unwrap();
+    assert!(mesh.normals.is_empty());
+
+    let mesh = obj_load("data/obj/uvs.obj").unwrap();
+    assert_eq!(mesh.triangle_count(), 2);
+    assert_eq!(mesh.uvs[4], Vector2f(1.0, 1.0));
+    assert_eq!(mesh.normals, vec![Vector3f(0.0, 0.0, 1.0); 6]);
+
+    let mesh = obj_load("data/obj/normals.obj").unwrap();
+    assert_eq!(mesh.triangle_count(), 2);
+    assert!(mesh.uvs.is_empty());
+    assert_eq!(mesh.normals, vec![Vector3f(0.0, 0.0, 1.0); 6]);
+
+    let mesh = obj_load("data/obj/full.obj").unwrap();
+    assert_eq!(mesh.triangle_count(), 1);
+    assert_eq!(mesh.uvs[1], Vector2f(1.0, 0.0));
+    assert_eq!(mesh.normals[2], Vector3f(0.0, 0.0, 1.0));
+
+    // Missing UVs and normals are filled with zeros.
+    let mesh = obj_load_with("data/obj/mixed.obj", &without_normals).unwrap();
+    assert_eq!(mesh.triangle_count(), 2);
+    assert_eq!(mesh.uvs[..3], [Vector2f(0.5, 0.5); 3]);
+    assert_eq!(mesh.uvs[3..], [Vector2f(0.0, 0.0); 3]);
+    assert_eq!(mesh.normals[..3], [Vector3f(0.0, 0.0, 1.0); 3]);
+    assert_eq!(mesh.normals[3..], [Vector3f(0.0, 0.0, 0.0); 3]);
+}
+
+#[test]
+fn partial_normals() {
+    // Normals missing on some faces are generated.
+    for path in ["data/obj/partial_normals.obj", "data/obj/mixed.obj"].iter() {
+        let mesh = obj_load(path).unwrap();
+        for n in &mesh.normals {
+            assert!((n.length() - 1.0).abs() < 1e-6, "{}: {:?}", path, n);
+        }
+    }
+
+    let mesh = obj_load("data/obj/partial_normals.obj").unwrap();
+    assert_eq!(mesh.normals[..3], [Vector3f(0.0, 0.0, 1.0); 3]);
+    assert_eq!(mesh.normals[3..], [Vector3f(0.0, 0.0, -1.0); 3]);
+}
+
+#[test]
+fn negative_indices() {
+    let mesh = obj_load("data/obj/negative.obj").unwrap();
+
+    assert_eq!(mesh.triangle_count(), 2);
+    assert_eq!(mesh.positions[0], Vector3f(0.0, 0.0, 0.0));
+    assert_eq!(mesh.positions[5], Vector3f(0.0, 1.0, 1.0));
+    assert_eq!(mesh.normals, vec![Vector3f(0.0, 0.0, 1.0); 6]);
+
+    let e = load("negative", &format!("{}f -4 -2 -1\n", VERTICES)).unwrap_err();
+    assert_eq!(location(&e), (6, 3));
+    match e.kind {
+        ObjErrorKind::IndexOutOfRange(i) => assert_eq!(i, -4),
+        ref kind => panic!("{:?}", kind),
+    }
+}
+
+#[test]
+fn polygons() {
+    let ear_clipping = ObjOptions {
+        triangulation: Triangulation::EarClipping,
+        ..ObjOptions::default()
+    };
+
+    for options in [ObjOptions::default(), ear_clipping.clone()].iter() {
+        let mesh = obj_load_with("data/obj/hexagon.obj", options).unwrap();
+        assert_eq!(mesh.triangle_count(), 4);
+        let areas = areas(&mesh);
+        assert!(areas.iter().all(|&a| a > 0.0), "{:?}", areas);
+        assert!((areas.iter().sum::<f32>() - 2.598_076).abs() < 1e-5);
+    }
+
+    // Fan goes out of concave polygon.
+    let mesh = obj_load("data/obj/concave.obj").unwrap();
+    assert!(areas(&mesh).iter().any(|&a| a < 0.0));
+
+    let mesh = obj_load_with("data/obj/concave.obj", &ear_clipping).unwrap();
+    assert_eq!(mesh.triangle_count(), 4);
+    let areas = areas(&mesh);
+    assert!(areas.iter().all(|&a| a > 0.0), "{:?}", areas);
+    assert_eq!(areas.iter().sum::<f32>(), 3.0);
+}
+
+#[test]
+fn materials() {
+    let mesh = obj_load("data/obj/materials.obj").unwrap();
+
+    assert_eq!(mesh.materials.len(), 2);
+    let brick = &mesh.materials[0];
+    assert_eq!(brick.name, "brick wall");
+    assert_eq!(brick.ambient, Rgb::new(0.1, 0.1, 0.1));
+    assert_eq!(brick.diffuse, Rgb::new(0.8, 0.4, 0.3));
+    assert_eq!(brick.specular, Rgb::new(0.2, 0.2, 0.2));
+    assert_eq!((brick.shininess, brick.opacity), (10.0, 1.0));
+    assert_eq!(brick.illumination, 2);
+    // Textures are relative to material library.
+    assert_eq!(
+        brick.diffuse_map,
+        Some(Path::new("data/obj/textures/brick wall.png").to_owned())
+    );
+    assert_eq!(
+        brick.bump_map,
+        Some(Path::new("data/obj/textures/brick_normal.png").to_owned())
+    );
+    assert_eq!(brick.specular_map, None);
+
+    let metal = &mesh.materials[1];
+    assert_eq!(metal.name, "metal");
+    assert_eq!(metal.opacity, 0.75);
+    assert_eq!(
+        metal.specular_map,
+        Some(Path::new("data/obj/metal_specular.png").to_owned())
+    );
+    assert_eq!(metal.diffuse_map, None);
+
+    // Unknown material gives submesh without material.
+    let submeshes: Vec<_> = mesh
+        .submeshes
+        .iter()
+        .map(|s| (s.range.clone(), s.material))
+        .collect();
+    assert_eq!(
+        submeshes,
+        vec![(0..6, Some(0)), (6..12, Some(1)), (12..15, None)]
+    );
+
+    // Material library of this cube isn't shipped.
+    let mesh = obj_load("data/tut08/cube.obj").unwrap();
+    assert!(mesh.materials.is_empty());
+    assert_eq!(mesh.submeshes[0].material, None);
+
+    let mesh = obj_load("data/tut07/cube.obj").unwrap();
+    assert_eq!(mesh.submeshes[0].material, Some(0));
+    assert_eq!(
+        mesh.materials[0].diffuse_map,
+        Some(Path::new("data/tut07/uvmap.DDS").to_owned())
+    );
+}
+
+#[test]
+fn groups() {
+    let mesh = obj_load("data/obj/groups.obj").unwrap();
+
+    // Unnamed group belongs to the object.
+    let submeshes: Vec<_> = mesh
+        .submeshes
+        .iter()
+        .map(|s| (s.range.clone(), s.name.as_ref().unwrap().as_str()))
+        .collect();
+    assert_eq!(
+        submeshes,
+        vec![
+            (0..6, "floor"),
+            (6..12, "front"),
+            (12..18, "back"),
+            (18..21, "roof")
+        ]
+    );
+    assert_eq!(mesh.smoothing_groups, vec![0, 0, 1, 1, 1, 1, 0]);
+
+    // Files without smoothing groups are smooth.
+    assert!(obj_load("data/obj/materials.obj")
+        .unwrap()
+        .smoothing_groups
+        .is_empty());
+
+    let e = load("bad-smoothing-group", &format!("{}s on\n", VERTICES)).unwrap_err();
+    assert_eq!(location(&e), (6, 3));
+    match e.kind {
+        ObjErrorKind::BadIndex(ref s) => assert_eq!(s, "on"),
+        ref kind => panic!("{:?}", kind),
+    }
+}
+
+#[test]
+fn material_errors() {
+    let e = mtl_load("data/obj/materials.obj").unwrap_err();
+    assert_eq!(location(&e), (2, 1));
+    match e.kind {
+        ObjErrorKind::MissingComponent(what) => assert_eq!(what, "newmtl"),
+        ref kind => panic!("{:?}", kind),
+    }
+
+    // Errors in library point to both files.
+    let mtl = env::temp_dir().join("tutcommon-bad.mtl");
+    fs::write(&mtl, "newmtl bad\nKd 1 0.5 x\n").unwrap();
+    let e = load("bad-material", "\nmtllib tutcommon-bad.mtl\n").unwrap_err();
+    fs::remove_file(&mtl).unwrap();
+
+    assert_eq!(location(&e), (2, 8));
+    let inner = match e.kind {
+        ObjErrorKind::Material(ref inner) => inner,
+        ref kind => panic!("{:?}", kind),
+    };
+    assert_eq!(inner.file, mtl);
+    assert_eq!(location(inner), (2, 10));
+    assert!(e.source().is_some());
+    assert!(e
+        .to_string()
+        .ends_with(&format!("in material library: {}", inner)));
+}
@@ -0,0 +1,98 @@
+extern crate tutcommon;
+
+use tutcommon::matrix::{
+    Matrix3d, Matrix3f, Matrix4d, Matrix4f, Quaternion, Scalar, Transform, Vector2, Vector2d,
+    Vector2f, Vector3, Vector3d, Vector3f, Vector4d, Vector4f,
+};
+
+#[test]
+fn f64_keeps_large_world_coordinates() {
+    // f32 has 24 bits of mantissa, so 0.25 is lost at 1e7.
+    let far: Vector3d = Vector3(1.0e7, 0.0, 0.0);
+    let step: Vector3d = Vector3(0.25, 0.0, 0.0);
+    assert_eq!((far + step - far).0, 0.25);
+
+    let farf = Vector3f(1.0e7, 0.0, 0.0);
+    let stepf = Vector3f(0.25, 0.0, 0.0);
+    assert_ne!((farf + stepf - farf).0, 0.25);
+
+    // Camera-relative position is cast to f32 for upload.
+    let eye = Vector3(1.0e7, 2.0, 3.0);
+    let relative: Vector3f = (far + step - eye).cast();
+    assert_eq!(relative, Vector3f(0.25, -2.0, -3.0));
+}
+
+#[test]
+fn f64_matrix_operations() {
+    let m = Matrix4d::translate(&Vector3(1.0e7, -2.0, 3.0))
+        .mul(&Matrix4d::rotate(30.0, &Vector3(0.0, 1.0, 0.0)))
+        .mul(&Matrix4d::scale(&Vector3(2.0, 2.0, 2.0)));
+    let p = Vector3(0.125, 0.5, -0.25);
+
+    let back = m.inverse().unwrap().transform_point(&m.transform_point(&p));
+    assert!(back.distance(&p) < 1e-8, "{:?} != {:?}", back, p);
+
+    let n = Matrix3d::from_columns([[2.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 8.0]]);
+    assert_eq!(n.determinant(), 64.0);
+    assert_eq!(n.inverse().unwrap().columns()[2][2], 0.125);
+}
+
+#[test]
+fn lossless_conversion_into_f64() {
+    let v = Vector4f(0.1, -2.5, 3.0, 1.0);
+    let d: Vector4d = v.into();
+    assert_eq!(d.0, f64::from(0.1f32));
+    assert_eq!(d.cast::<f32>(), v);
+
+    let w: Vector2d = Vector2f(1.5, -0.1).into();
+    assert_eq!(w, Vector2(1.5, f64::from(-0.1f32)));
+
+    let m = Matrix4f::perspective(60.0, 4.0 / 3.0, 0.1, 100.0);
+    let md = Matrix4d::from(m.clone());
+    assert_eq!(md.cast::<f32>(), m);
+
+    let m3 = Matrix3f::from_columns([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 0.1]]);
+    assert_eq!(Matrix3d::from(m3.clone()).cast::<f32>(), m3);
+
+    let q = Quaternion::from_euler(10.0, 20.0, 30.0);
+    let qd: Quaternion<f64> = q.into();
+    assert_eq!(qd.cast::<f32>(), q);
+
+    let t = Transform {
+        translation: Vector3f(1.0, 2.0, 3.0),
+        rotation: q,
+        scale: Vector3f(0.5, 0.5, 0.5),
+    };
+    let td: Transform<f64> = t.into();
+    assert_eq!(td.cast::<f32>(), t);
+}
+
+#[test]
+fn lossy_conversion_rounds_to_nearest() {
+    let d: Vector3d = Vector3(0.1, 1.0e-50, 1.0e50);
+    let f: Vector3f = d.cast();
+    assert_eq!(f, Vector3f(0.1, 0.0, f32::INFINITY));
+
+    let m = Matrix4d::translate(&Vector3(1.0e7 + 0.25, 0.0, 0.0));
+    assert_eq!(m.cast::<f32>().columns()[3][0], 1.0e7);
+
+    assert_eq!(0.1f64.cast::<f32>(), 0.1f32);
+}
+
+#[test]
+fn generic_code_over_scalar() {
+    fn centroid<T: Scalar>(points: &[Vector3<T>]) -> Vector3<T> {
+        let mut sum = Vector3::default();
+        for p in points {
+            sum += p;
+        }
+
+        sum / T::from_f64(points.len() as f64)
+    }
+
+    let pf = [Vector3f(0.0, 0.0, 0.0), Vector3f(2.0, 4.0, 6.0)];
+    assert_eq!(centroid(&pf), Vector3f(1.0, 2.0, 3.0));
+
+    let pd: [Vector3d; 2] = [Vector3(0.0, 0.0, 0.0), Vector3(2.0, 4.0, 6.0)];
+    assert_eq!(centroid(&pd), Vector3(1.0, 2.0, 3.0));
+}
@@ -0,0 +1,119 @@
+extern crate proptest;
+extern crate tutcommon;
+
+use proptest::prelude::*;
+use tutcommon::matrix::{ApproxEq, Matrix4f, Vector3f};
+
+fn vector(range: f32) -> impl Strategy<Value = Vector3f> {
+    (-range..range, -range..range, -range..range).prop_map(|(x, y, z)| Vector3f(x, y, z))
+}
+
+fn matrix() -> impl Strategy<Value = Matrix4f> {
+    prop::array::uniform4(prop::array::uniform4(-10.0f32..10.0)).prop_map(Matrix4f::from_columns)
+}
+
+fn max_element(m: &Matrix4f) -> f32 {
+    m.columns()
+        .iter()
+        .flatten()
+        .fold(0.0f32, |m, x| m.max(x.abs()))
+}
+
+// Tolerance relative to the largest element of matrices.
+fn assert_matrix_eq(a: &Matrix4f, b: &Matrix4f, relative: f32) -> Result<(), TestCaseError> {
+    let max = max_element(a).max(max_element(b)).max(1.0);
+
+    prop_assert!(a.abs_diff_eq(b, max * relative), "{:?} != {:?}", a, b);
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn look_at_is_orthonormal(eye in vector(100.0), center in vector(100.0), up in vector(1.0)) {
+        let dir = center - eye;
+        prop_assume!(dir.length() > 1e-2 && up.length() > 1e-2);
+        prop_assume!(dir.normalize().cross(&up.normalize()).length() > 1e-2);
+
+        let m = Matrix4f::look_at(&eye, &center, &up);
+        let r = m.to_matrix3();
+
+        prop_assert!(r.mul(&r.transpose()).abs_diff_eq(&Default::default(), 1e-5));
+        prop_assert!(r.determinant().abs_diff_eq(&1.0, 1e-5));
+
+        // Camera is at origin and looks along -Z.
+        let e = m.transform_point(&eye);
+        prop_assert!(e.abs_diff_eq(&Vector3f(0.0, 0.0, 0.0), 1e-3), "{:?}", e);
+        let c = m.transform_point(&center);
+        prop_assert!(c.abs_diff_eq(&Vector3f(0.0, 0.0, -dir.length()), 1e-3), "{:?}", c);
+    }
+
+    #[test]
+    fn rotate_preserves_length(angle in -720.0f32..720.0, axis in vector(1.0), v in vector(100.0)) {
+        prop_assume!(axis.length() > 1e-2);
+
+        let r = Matrix4f::rotate(angle, &axis);
+        let rotated = r.transform_direction(&v);
+
+        prop_assert!(
+            rotated.length().abs_diff_eq(&v.length(), 1e-5 * v.length().max(1.0)),
+            "{:?} -> {:?}",
+            v,
+            rotated
+        );
+        // Points on the axis stay in place.
+        let on_axis = axis * 3.0;
+        prop_assert!(r.transform_direction(&on_axis).abs_diff_eq(&on_axis, 1e-4));
+    }
+
+    #[test]
+    fn mul_is_associative(a in matrix(), b in matrix(), c in matrix()) {
+        let left = a.mul(&b).mul(&c);
+        let right = a.mul(&b.mul(&c));
+
+        assert_matrix_eq(&left, &right, 1e-5)?;
+    }
+
+    #[test]
+    fn perspective_maps_near_and_far(
+        fov in 10.0f32..170.0,
+        aspect in 0.2f32..5.0,
+        znear in 0.01f32..10.0,
+        depth in 1.0f32..1000.0,
+    ) {
+        let zfar = znear + depth;
+        let p = Matrix4f::perspective(fov, aspect, znear, zfar);
+
+        let near = p.transform_point(&Vector3f(0.0, 0.0, -znear));
+        let far = p.transform_point(&Vector3f(0.0, 0.0, -zfar));
+        prop_assert!(near.2.abs_diff_eq(&-1.0, 1e-4), "near {:?}", near);
+        prop_assert!(far.2.abs_diff_eq(&1.0, 1e-4), "far {:?}", far);
+
+        // Top of view volume at near plane goes to top of clip space.
+        let top = znear * (fov.to_radians() / 2.0).tan();
+        let corner = p.transform_point(&Vector3f(top * aspect, top, -znear));
+        prop_assert!(corner.abs_diff_eq(&Vector3f(1.0, 1.0, -1.0), 1e-3), "{:?}", corner);
+    }
+
+    #[test]
+    fn ortho_inverts(
+        left in -100.0f32..100.0,
+        bottom in -100.0f32..100.0,
+        znear in -100.0f32..100.0,
+        size in (0.1f32..100.0, 0.1f32..100.0, 0.1f32..100.0),
+    ) {
+        let (right, top, zfar) = (left + size.0, bottom + size.1, znear + size.2);
+        let o = Matrix4f::ortho(left, right, bottom, top, znear, zfar);
+        let inv = o.inverse().unwrap();
+
+        // Rounding error of product grows with both matrices for narrow
+        // volumes far from origin.
+        let tolerance = 1e-6 * max_element(&o) * max_element(&inv);
+        assert_matrix_eq(&o.mul(&inv), &Default::default(), tolerance.max(1e-5))?;
+
+        let min = inv.transform_point(&Vector3f(-1.0, -1.0, -1.0));
+        let max = inv.transform_point(&Vector3f(1.0, 1.0, 1.0));
+        prop_assert!(min.abs_diff_eq(&Vector3f(left, bottom, -znear), 1e-3), "{:?}", min);
+        prop_assert!(max.abs_diff_eq(&Vector3f(right, top, -zfar), 1e-3), "{:?}", max);
+    }
+}
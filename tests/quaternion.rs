@@ -0,0 +1,170 @@
+extern crate tutcommon;
+
+use tutcommon::matrix::{Matrix4f, Quaternion, Vector3f};
+
+fn assert_vector_eq(a: &Vector3f, b: &Vector3f) {
+    assert!(
+        (a.0 - b.0).abs() < 1e-5 && (a.1 - b.1).abs() < 1e-5 && (a.2 - b.2).abs() < 1e-5,
+        "{:?} != {:?}",
+        a,
+        b
+    );
+}
+
+// q and -q are the same rotation.
+fn assert_rotation_eq(a: &Quaternion, b: &Quaternion) {
+    assert!((a.dot(b).abs() - 1.0).abs() < 1e-5, "{:?} != {:?}", a, b);
+}
+
+fn assert_matrix_eq(a: &Matrix4f, b: &Matrix4f) {
+    for (col_a, col_b) in a.columns().iter().zip(b.columns().iter()) {
+        for (x, y) in col_a.iter().zip(col_b.iter()) {
+            assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+}
+
+#[test]
+fn axis_angle_rotates_vector() {
+    let q = Quaternion::from_axis_angle(90.0, &Vector3f(0.0, 0.0, 2.0));
+
+    assert!((q.length() - 1.0).abs() < 1e-6);
+    assert_vector_eq(
+        &q.rotate_vector(&Vector3f(1.0, 0.0, 0.0)),
+        &Vector3f(0.0, 1.0, 0.0),
+    );
+    assert_vector_eq(
+        &q.rotate_vector(&Vector3f(0.0, 0.0, 3.0)),
+        &Vector3f(0.0, 0.0, 3.0),
+    );
+
+    let (axis, angle) = q.to_axis_angle();
+    assert_vector_eq(&axis, &Vector3f(0.0, 0.0, 1.0));
+    assert!((angle - 90.0).abs() < 1e-4);
+}
+
+#[test]
+fn multiplication_composes_rotations() {
+    let a = Quaternion::from_axis_angle(90.0, &Vector3f(0.0, 1.0, 0.0));
+    let b = Quaternion::from_axis_angle(90.0, &Vector3f(1.0, 0.0, 0.0));
+    let v = Vector3f(0.0, 0.0, 1.0);
+
+    // b first, then a.
+    assert_vector_eq(
+        &a.mul(&b).rotate_vector(&v),
+        &a.rotate_vector(&b.rotate_vector(&v)),
+    );
+    assert_matrix_eq(&a.mul(&b).to_matrix(), &a.to_matrix().mul(&b.to_matrix()));
+}
+
+#[test]
+fn euler_angles_match_axis_rotations() {
+    assert_rotation_eq(
+        &Quaternion::from_euler(30.0, 0.0, 0.0),
+        &Quaternion::from_axis_angle(30.0, &Vector3f(1.0, 0.0, 0.0)),
+    );
+    assert_rotation_eq(
+        &Quaternion::from_euler(0.0, 30.0, 0.0),
+        &Quaternion::from_axis_angle(30.0, &Vector3f(0.0, 1.0, 0.0)),
+    );
+
+    let expected = Matrix4f::rotate(50.0, &Vector3f(0.0, 0.0, 1.0))
+        .mul(&Matrix4f::rotate(40.0, &Vector3f(0.0, 1.0, 0.0)))
+        .mul(&Matrix4f::rotate(30.0, &Vector3f(1.0, 0.0, 0.0)));
+    assert_matrix_eq(
+        &Quaternion::from_euler(30.0, 40.0, 50.0).to_matrix(),
+        &expected,
+    );
+}
+
+#[test]
+fn conjugate_and_inverse_undo_rotation() {
+    let q = Quaternion::from_axis_angle(70.0, &Vector3f(1.0, 2.0, 3.0));
+    let v = Vector3f(-1.0, 4.0, 2.0);
+
+    assert_vector_eq(&q.conjugate().rotate_vector(&q.rotate_vector(&v)), &v);
+    assert_rotation_eq(&q.mul(&q.inverse().unwrap()), &Quaternion::default());
+
+    let scaled: Quaternion = Quaternion {
+        x: 0.0,
+        y: 0.0,
+        z: 2.0,
+        w: 2.0,
+    };
+    let inv = scaled.inverse().unwrap();
+    let id = scaled.mul(&inv);
+    assert!((id.w - 1.0).abs() < 1e-6 && id.x.abs() < 1e-6 && id.z.abs() < 1e-6);
+    assert!((scaled.normalize().length() - 1.0).abs() < 1e-6);
+
+    let zero: Quaternion = Quaternion {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        w: 0.0,
+    };
+    assert!(zero.inverse().is_none());
+}
+
+#[test]
+fn matrix_round_trip() {
+    let rotations = [
+        Quaternion::default(),
+        Quaternion::from_axis_angle(10.0, &Vector3f(1.0, 0.0, 0.0)),
+        Quaternion::from_axis_angle(179.0, &Vector3f(1.0, 0.0, 0.0)),
+        Quaternion::from_axis_angle(180.0, &Vector3f(0.0, 1.0, 0.0)),
+        Quaternion::from_axis_angle(180.0, &Vector3f(0.0, 0.0, 1.0)),
+        Quaternion::from_axis_angle(123.0, &Vector3f(-1.0, 2.0, 0.5)),
+        Quaternion::from_euler(10.0, 200.0, -30.0),
+    ];
+
+    for q in rotations.iter() {
+        let m = q.to_matrix();
+        assert_rotation_eq(&Quaternion::from_matrix(&m), q);
+        assert_matrix_eq(&Quaternion::from_matrix(&m).to_matrix(), &m);
+    }
+
+    // Translation doesn't affect rotation part.
+    let m = Matrix4f::translate(&Vector3f(5.0, 6.0, 7.0)).mul(&rotations[5].to_matrix());
+    assert_rotation_eq(&Quaternion::from_matrix(&m), &rotations[5]);
+}
+
+#[test]
+fn interpolation() {
+    let a = Quaternion::default();
+    let b = Quaternion::from_axis_angle(90.0, &Vector3f(0.0, 1.0, 0.0));
+
+    assert_rotation_eq(&a.slerp(&b, 0.0), &a);
+    assert_rotation_eq(&a.slerp(&b, 1.0), &b);
+    assert_rotation_eq(
+        &a.slerp(&b, 0.5),
+        &Quaternion::from_axis_angle(45.0, &Vector3f(0.0, 1.0, 0.0)),
+    );
+    assert_rotation_eq(
+        &a.slerp(&b, 0.25),
+        &Quaternion::from_axis_angle(22.5, &Vector3f(0.0, 1.0, 0.0)),
+    );
+    assert_rotation_eq(
+        &a.nlerp(&b, 0.5),
+        &Quaternion::from_axis_angle(45.0, &Vector3f(0.0, 1.0, 0.0)),
+    );
+
+    // nlerp isn't constant speed.
+    let (_, angle) = a.nlerp(&b, 0.25).to_axis_angle();
+    assert!((angle - 22.5).abs() > 0.1);
+
+    // Shortest path is taken for the opposite sign quaternion.
+    let neg_b = Quaternion {
+        x: -b.x,
+        y: -b.y,
+        z: -b.z,
+        w: -b.w,
+    };
+    assert_rotation_eq(
+        &a.slerp(&neg_b, 0.5),
+        &Quaternion::from_axis_angle(45.0, &Vector3f(0.0, 1.0, 0.0)),
+    );
+    assert_rotation_eq(
+        &a.nlerp(&neg_b, 0.5),
+        &Quaternion::from_axis_angle(45.0, &Vector3f(0.0, 1.0, 0.0)),
+    );
+}
@@ -0,0 +1,58 @@
+extern crate tutcommon;
+
+use tutcommon::matrix::{Matrix4f, Quaternion, Vector3f, Vector4f};
+
+// Scalar multiplication in the same order as in `Matrix4f::mul`.
+fn reference_mul(a: &Matrix4f, b: &Matrix4f) -> Matrix4f {
+    let (a, b) = (a.columns(), b.columns());
+    let mut res = [[0.0; 4]; 4];
+
+    for i in 0..4 {
+        for j in 0..4 {
+            for k in 0..4 {
+                res[j][i] += a[k][i] * b[j][k];
+            }
+        }
+    }
+
+    Matrix4f::from_columns(res)
+}
+
+fn matrices() -> Vec<Matrix4f> {
+    (0..50)
+        .map(|i| {
+            let f = i as f32 * 0.37;
+            Matrix4f::translate(&Vector3f(f.sin() * 100.0, f.cos(), -f))
+                .mul(&Quaternion::from_euler(f * 30.0, f * 17.0, -f * 11.0).to_matrix())
+                .mul(&Matrix4f::scale(&Vector3f(1.0 + f, 0.5, 3.0 - f * 0.1)))
+                .mul(&Matrix4f::perspective(
+                    30.0 + f,
+                    1.3,
+                    0.1 * f + 0.01,
+                    1000.0,
+                ))
+        })
+        .collect()
+}
+
+#[test]
+fn matrix_mul_matches_scalar_reference() {
+    let ms = matrices();
+
+    for a in ms.iter() {
+        for b in ms.iter() {
+            assert_eq!(a.mul(b), reference_mul(a, b));
+        }
+    }
+}
+
+#[test]
+fn matrix_vector_mul_matches_scalar_reference() {
+    for m in matrices().iter() {
+        let v = Vector4f(1.5, -2.0, 0.25, 1.0);
+        let as_matrix = Matrix4f::from_columns([[1.5, -2.0, 0.25, 1.0]; 4]);
+
+        let expected = reference_mul(m, &as_matrix).columns()[0];
+        assert_eq!(m * v, Vector4f::from(expected));
+    }
+}
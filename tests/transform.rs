@@ -0,0 +1,120 @@
+extern crate tutcommon;
+
+use tutcommon::matrix::{Matrix4f, Quaternion, Transform, Vector3f};
+
+fn assert_matrix_eq(a: &Matrix4f, b: &Matrix4f) {
+    for (col_a, col_b) in a.columns().iter().zip(b.columns().iter()) {
+        for (x, y) in col_a.iter().zip(col_b.iter()) {
+            assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+}
+
+fn assert_vector_eq(a: &Vector3f, b: &Vector3f) {
+    assert!(a.distance(b) < 1e-4, "{:?} != {:?}", a, b);
+}
+
+fn assert_rotation_eq(a: &Quaternion, b: &Quaternion) {
+    assert!((a.dot(b).abs() - 1.0).abs() < 1e-5, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn decompose_trs_matrix() {
+    let rotation = Quaternion::from_euler(20.0, -35.0, 110.0);
+    let m = Matrix4f::translate(&Vector3f(1.0, -2.0, 3.0))
+        .mul(&rotation.to_matrix())
+        .mul(&Matrix4f::scale(&Vector3f(2.0, 0.5, 3.0)));
+
+    let (t, r, s) = m.decompose().unwrap();
+    assert_vector_eq(&t, &Vector3f(1.0, -2.0, 3.0));
+    assert_rotation_eq(&r, &rotation);
+    assert_vector_eq(&s, &Vector3f(2.0, 0.5, 3.0));
+
+    assert!(Matrix4f::scale(&Vector3f(1.0, 0.0, 1.0))
+        .decompose()
+        .is_none());
+}
+
+#[test]
+fn decompose_mirrored_matrix() {
+    let m = Matrix4f::rotate(30.0, &Vector3f(0.0, 1.0, 0.0))
+        .mul(&Matrix4f::scale(&Vector3f(1.0, -2.0, 1.0)));
+
+    let (_, _, s) = m.decompose().unwrap();
+    assert!(s.0 < 0.0);
+    assert_matrix_eq(&Transform::from_matrix(&m).unwrap().to_matrix(), &m);
+}
+
+#[test]
+fn transform_matrix_round_trip() {
+    let transform = Transform {
+        translation: Vector3f(5.0, 0.0, -1.0),
+        rotation: Quaternion::from_axis_angle(60.0, &Vector3f(1.0, 1.0, 0.0)),
+        scale: Vector3f(1.0, 2.0, 3.0),
+    };
+
+    let m = transform.to_matrix();
+    let back = Transform::from_matrix(&m).unwrap();
+    assert_vector_eq(&back.translation, &transform.translation);
+    assert_rotation_eq(&back.rotation, &transform.rotation);
+    assert_vector_eq(&back.scale, &transform.scale);
+
+    let p = Vector3f(1.0, -1.0, 2.0);
+    assert_vector_eq(&transform.transform_point(&p), &m.transform_point(&p));
+    assert_vector_eq(
+        &transform.transform_direction(&p),
+        &m.transform_direction(&p),
+    );
+
+    assert_matrix_eq(&Transform::default().to_matrix(), &Matrix4f::default());
+}
+
+#[test]
+fn compose_and_inverse() {
+    let parent = Transform {
+        translation: Vector3f(0.0, 10.0, 0.0),
+        rotation: Quaternion::from_axis_angle(90.0, &Vector3f(0.0, 1.0, 0.0)),
+        scale: Vector3f(2.0, 2.0, 2.0),
+    };
+    let child = Transform {
+        translation: Vector3f(1.0, 0.0, 0.0),
+        rotation: Quaternion::from_axis_angle(45.0, &Vector3f(1.0, 0.0, 0.0)),
+        scale: Vector3f(1.0, 3.0, 1.0),
+    };
+
+    assert_matrix_eq(
+        &parent.mul(&child).to_matrix(),
+        &parent.to_matrix().mul(&child.to_matrix()),
+    );
+
+    let inv = parent.inverse().unwrap();
+    assert_matrix_eq(&inv.to_matrix(), &parent.to_matrix().inverse().unwrap());
+    assert_matrix_eq(&parent.mul(&inv).to_matrix(), &Matrix4f::default());
+
+    let flat = Transform {
+        scale: Vector3f(1.0, 0.0, 1.0),
+        ..Default::default()
+    };
+    assert!(flat.inverse().is_none());
+}
+
+#[test]
+fn interpolation() {
+    let a = Transform::default();
+    let b = Transform {
+        translation: Vector3f(10.0, 0.0, 0.0),
+        rotation: Quaternion::from_axis_angle(90.0, &Vector3f(0.0, 0.0, 1.0)),
+        scale: Vector3f(3.0, 3.0, 3.0),
+    };
+
+    let mid = a.lerp(&b, 0.5);
+    assert_vector_eq(&mid.translation, &Vector3f(5.0, 0.0, 0.0));
+    assert_rotation_eq(
+        &mid.rotation,
+        &Quaternion::from_axis_angle(45.0, &Vector3f(0.0, 0.0, 1.0)),
+    );
+    assert_vector_eq(&mid.scale, &Vector3f(2.0, 2.0, 2.0));
+
+    assert_eq!(a.lerp(&b, 0.0), a);
+    assert_matrix_eq(&a.lerp(&b, 1.0).to_matrix(), &b.to_matrix());
+}
@@ -0,0 +1,110 @@
+#![allow(clippy::op_ref)]
+
+extern crate tutcommon;
+
+use tutcommon::matrix::{Vector2f, Vector3f, Vector4f};
+
+#[test]
+fn arithmetic_operators() {
+    let a = Vector3f(1.0, 2.0, 3.0);
+    let b = Vector3f(4.0, -5.0, 6.0);
+
+    assert_eq!(a + b, Vector3f(5.0, -3.0, 9.0));
+    assert_eq!(&a + &b, Vector3f(5.0, -3.0, 9.0));
+    assert_eq!(a + &b, &a + b);
+    assert_eq!(a - b, Vector3f(-3.0, 7.0, -3.0));
+    assert_eq!(&a - &b, Vector3f(-3.0, 7.0, -3.0));
+    assert_eq!(a * b, Vector3f(4.0, -10.0, 18.0));
+    assert_eq!(b / a, Vector3f(4.0, -2.5, 2.0));
+    assert_eq!(a * 2.0, Vector3f(2.0, 4.0, 6.0));
+    assert_eq!(&a * 2.0, Vector3f(2.0, 4.0, 6.0));
+    assert_eq!(2.0 * a, Vector3f(2.0, 4.0, 6.0));
+    assert_eq!(2.0 * &a, Vector3f(2.0, 4.0, 6.0));
+    assert_eq!(a / 2.0, Vector3f(0.5, 1.0, 1.5));
+    assert_eq!(-a, Vector3f(-1.0, -2.0, -3.0));
+    assert_eq!(-&a, Vector3f(-1.0, -2.0, -3.0));
+
+    assert_eq!(Vector2f(1.0, 2.0) + Vector2f(3.0, 4.0), Vector2f(4.0, 6.0));
+    assert_eq!(
+        Vector4f(1.0, 2.0, 3.0, 4.0) * 0.5,
+        Vector4f(0.5, 1.0, 1.5, 2.0)
+    );
+}
+
+#[test]
+fn assignment_operators() {
+    let mut v = Vector3f(1.0, 2.0, 3.0);
+
+    v += Vector3f(1.0, 1.0, 1.0);
+    assert_eq!(v, Vector3f(2.0, 3.0, 4.0));
+    v -= &Vector3f(1.0, 0.0, 1.0);
+    assert_eq!(v, Vector3f(1.0, 3.0, 3.0));
+    v *= 2.0;
+    assert_eq!(v, Vector3f(2.0, 6.0, 6.0));
+    v /= Vector3f(2.0, 3.0, 6.0);
+    assert_eq!(v, Vector3f(1.0, 2.0, 1.0));
+    v *= Vector3f(3.0, 3.0, 3.0);
+    v /= 3.0;
+    assert_eq!(v, Vector3f(1.0, 2.0, 1.0));
+}
+
+#[test]
+fn products_and_length() {
+    let a = Vector3f(1.0, 2.0, 2.0);
+
+    assert_eq!(a.dot(&Vector3f(2.0, 0.0, -1.0)), 0.0);
+    assert_eq!(a.length_squared(), 9.0);
+    assert_eq!(a.length(), 3.0);
+    assert_eq!(a.distance(&Vector3f(1.0, 2.0, 6.0)), 4.0);
+    assert!((a.normalize().length() - 1.0).abs() < 1e-6);
+
+    assert_eq!(
+        Vector3f(1.0, 0.0, 0.0).cross(&Vector3f(0.0, 1.0, 0.0)),
+        Vector3f(0.0, 0.0, 1.0)
+    );
+    assert_eq!(Vector2f(3.0, 4.0).length(), 5.0);
+    assert_eq!(Vector4f(1.0, 1.0, 1.0, 1.0).length(), 2.0);
+}
+
+#[test]
+fn lerp_reflect_min_max() {
+    let a = Vector3f(0.0, 10.0, -2.0);
+    let b = Vector3f(4.0, 0.0, 2.0);
+
+    assert_eq!(a.lerp(&b, 0.0), a);
+    assert_eq!(a.lerp(&b, 1.0), b);
+    assert_eq!(a.lerp(&b, 0.25), Vector3f(1.0, 7.5, -1.0));
+
+    assert_eq!(
+        Vector3f(1.0, -1.0, 0.0).reflect(&Vector3f(0.0, 1.0, 0.0)),
+        Vector3f(1.0, 1.0, 0.0)
+    );
+
+    assert_eq!(a.min(&b), Vector3f(0.0, 0.0, -2.0));
+    assert_eq!(a.max(&b), Vector3f(4.0, 10.0, 2.0));
+    assert_eq!(Vector3f(-1.0, 2.0, -3.0).abs(), Vector3f(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn indexing_and_conversions() {
+    let mut v = Vector4f(1.0, 2.0, 3.0, 4.0);
+
+    assert_eq!(v[0], 1.0);
+    assert_eq!(v[3], 4.0);
+    v[1] = 5.0;
+    assert_eq!(v, Vector4f(1.0, 5.0, 3.0, 4.0));
+
+    assert_eq!(v.truncate(), Vector3f(1.0, 5.0, 3.0));
+    assert_eq!(Vector3f(1.0, 5.0, 3.0).extend(4.0), v);
+
+    let a: [f32; 4] = v.into();
+    assert_eq!(a, [1.0, 5.0, 3.0, 4.0]);
+    assert_eq!(Vector2f::from([7.0, 8.0]), Vector2f(7.0, 8.0));
+}
+
+#[test]
+#[should_panic]
+fn index_out_of_range() {
+    let v = Vector2f(1.0, 2.0);
+    let _ = v[2];
+}